@@ -1,7 +1,9 @@
 use sgui2::builders::{GuiBuilder, TabBuilder, WidgetData};
-use sgui2::{GuiEvent, WidgetState};
+use sgui2::{GuiEvent, WidgetState, Severity, FileBrowser, ImageSource, Graph, DateTime, DateTimeFields, Binding, StatusBar};
 use std::time::{Duration, Instant};
 use std::cell::Cell;
+use std::process::Command;
+use sdl2::keyboard::Keycode;
 
 fn widget_dbg(wdg: &mut WidgetState, _: &Cell<Instant>) {
     if let WidgetState::Slider(ref mut state, ..) = wdg {
@@ -20,24 +22,93 @@ fn animate(slider: &mut WidgetState, next: &Cell<Instant>) {
     next.set(Instant::now() + Duration::from_millis(10));
 }
 
+fn square((r, g, b): (u8, u8, u8)) -> ImageSource {
+    ImageSource::Rgba {
+        width: 16,
+        height: 16,
+        data: [r, g, b, 255].repeat(16 * 16),
+    }
+}
+
 fn main() {
     let mut gui_builder = GuiBuilder::new("Test program");
+    let notifier = gui_builder.notifier();
+    let (graph, feed) = Graph::new(60);
+    let status = StatusBar::new().clock().battery("/sys/class/power_supply/BAT0");
+    let status_handle = status.handle();
+    let launch = WidgetData::btn("Launch", widget_dbg).disabled();
+    let launch_flags = launch.flags();
     let mut gui = gui_builder.tab(TabBuilder::new("A tab")
-            .widget(WidgetData::btn("Example button", widget_dbg)))
+            .widget(WidgetData::btn("Example button", widget_dbg))
+            .widget(WidgetData::btn("Show a toast", move |_, _| notifier.notify("Hello from a callback!", Duration::from_secs(3), Severity::Info)))
+            .widget(WidgetData::toggle("Armed", move |state, _| {
+                if let WidgetState::Toggle(armed, _) = state {
+                    launch_flags.set_enabled(*armed);
+                }
+            }, false))
+            .widget(launch)
+            .widget(WidgetData::toggle("Wi-Fi indicator", move |state, _| {
+                if let WidgetState::Toggle(on, _) = state {
+                    if *on {
+                        status_handle.set_text("wifi", "Wi-Fi");
+                    } else {
+                        status_handle.remove("wifi");
+                    }
+                }
+            }, false)))
         .tab(TabBuilder::new("Second tab")
+            .description("Every kind of built-in widget")
             .widget(WidgetData::slider("Animated slider!", animate, 128))
             .widget(WidgetData::btn("Button with function", widget_dbg))
             .widget(WidgetData::btn("Button with closure", |b, _| println!("closure {:#?}", b)))
             .widget(WidgetData::btn("Button with an empty closure", |_, _| {}))
             .widget(WidgetData::toggle("A toggle with callback", |b, _| println!("{:#?}", b), true))
-            .widget(WidgetData::slider("I'm a slider!", widget_dbg, 128)))
+            .widget(WidgetData::slider("I'm a slider!", widget_dbg, 128)
+                .with_description("Callbacks can change the value, this one never goes past two thirds."))
+            .widget(WidgetData::color("LED color", widget_dbg, (255, 128, 0)).with_id("led_color"))
+            .widget(WidgetData::date_time("Alarm", widget_dbg, DateTime::new(2024, 2, 29, 7, 30), DateTimeFields::Both, true)))
+        .tab(TabBuilder::new("Controls")
+            .widget(WidgetData::key_capture("Jump", |b, _| println!("{:?}", b), Some(Binding::Key(Keycode::Space))).with_id("jump"))
+            .widget(WidgetData::key_capture("Fire", |b, _| println!("{:?}", b), None).with_id("fire")))
+        .tab(TabBuilder::new("Commands")
+            .description("Rows backed by shell commands, the file they keep their value in is in /tmp")
+            .widget(WidgetData::toggle_command("Slow toggle", "sleep 1; echo {} > /tmp/sgui2-toggle",
+                Some("cat /tmp/sgui2-toggle 2>/dev/null || echo off"), false))
+            .widget(WidgetData::slider_command("Slow slider", "sleep 0.3; echo {} > /tmp/sgui2-slider",
+                Some("cat /tmp/sgui2-slider 2>/dev/null || echo 0"), 0))
+            .widget(WidgetData::toggle_command("Always fails", "echo 'no permission' >&2; exit 1", None, false)))
+        .tab(TabBuilder::new("Monitor")
+            .widget(WidgetData::graph("Sine wave", graph.range(-1.0, 1.0))))
+        .tab(TabBuilder::new("Log")
+            .widget(WidgetData::log_command("Counting", Command::new("sh")
+                .args(["-c", "for i in $(seq 1 100); do echo line $i; echo error $i >&2; sleep 0.5; done"]), 50)
+                .expect("Failed to spawn sh")))
+        .tab(TabBuilder::new("Wallpaper")
+            .browser(FileBrowser::new("/usr/share")
+                .extensions(["png", "jpg", "jpeg"])
+                .on_select(|path| println!("picked {}", path.display()))))
+        .tab(TabBuilder::new("Launcher")
+            .grid()
+            .widget(WidgetData::tile("Terminal", square((40, 40, 40)), |_, _| println!("terminal")))
+            .widget(WidgetData::tile("Browser", square((40, 90, 200)), |_, _| println!("browser")))
+            .widget(WidgetData::btn("Settings", |_, _| println!("settings"))))
         .tab_separator()
         .tab(TabBuilder::new("Separated tab")
-            .widget(WidgetData::btn("A button with callback", |b, _| println!("{:#?}", b))))
-        .tab(&mut TabBuilder::new("Tab with a very long name to show off scrolling"))
+            .widget(WidgetData::btn("A button with callback", |b, _| println!("{:#?}", b)))
+            .radio_group_with_id("power_profile", ["Performance", "Balanced", "Powersave"], 1, |i| println!("radio {i}")))
+        .tab(TabBuilder::new("Tab with a very long name to show off scrolling")
+            .widget(WidgetData::paragraph("This paragraph is long enough that it has to be wrapped to the width of the panel, \
+                which changes when the window is resized.\nExplicit newlines start a new line.")))
+        .hint_bar(true)
+        .status_bar(status)
+        .persist_to(std::env::temp_dir().join("sgui2-builder-example.conf"))
+        // try `echo '{"cmd": "tree"}' | socat - UNIX-CONNECT:/tmp/sgui2-builder-example.sock`
+        .control_socket(std::env::temp_dir().join("sgui2-builder-example.sock"))
         .build();
 
+    let start = Instant::now();
     loop {
+        feed.push(start.elapsed().as_secs_f32().sin());
         if let Some(ev) = gui.tick() {
             eprintln!("{:#?}", ev);
             if ev == GuiEvent::Quit {
@@ -0,0 +1,43 @@
+use sgui2::builders::{GuiBuilder, TabBuilder, WidgetData};
+use sgui2::{ActionKey, Context, Control, GuiEvent, Theme};
+
+struct Counter {
+    value: i32,
+}
+
+impl Control for Counter {
+    fn draw(&mut self, ctx: &mut Context) {
+        let text = self.value.to_string();
+        let (width, _) = ctx.text_size(&text);
+        let x = ctx.width as i32 - width as i32 - ctx.label.height() as i32;
+        ctx.draw_text(&text, Theme::fg_widgets(), x, 0);
+    }
+    fn process_action(&mut self, action: &ActionKey) {
+        match action {
+            ActionKey::Left => self.value -= 1,
+            ActionKey::Right => self.value += 1,
+            _ => (),
+        }
+    }
+    fn grabs_input(&self) -> bool {
+        true
+    }
+    fn hints(&self, back: &'static str) -> Vec<(&'static str, &'static str)> {
+        vec![("\u{2190}\u{2192}", "Count"), (back, "Done")]
+    }
+}
+
+fn main() {
+    let mut gui = GuiBuilder::new("Custom widget")
+        .tab(TabBuilder::new("Custom")
+            .widget(WidgetData::custom("A counter", Counter { value: 0 })))
+        .build();
+
+    loop {
+        if let Some(ev) = gui.tick() {
+            if ev == GuiEvent::Quit {
+                return;
+            }
+        }
+    }
+}
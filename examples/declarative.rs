@@ -0,0 +1,26 @@
+use sgui2::builders::GuiBuilder;
+use sgui2::{Callbacks, GuiEvent};
+
+fn main() {
+    let mut callbacks = Callbacks::new();
+    callbacks
+        .add("print", |state, _| println!("{:?}", state))
+        .add_radio("governor", |i| println!("governor {i}"));
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/menu.toml");
+    let mut gui = match GuiBuilder::from_file(path, &callbacks) {
+        Ok(mut builder) => builder.build(),
+        Err(e) => {
+            eprintln!("{path}: {e}");
+            std::process::exit(1);
+        },
+    };
+
+    loop {
+        if let Some(ev) = gui.tick() {
+            if ev == GuiEvent::Quit {
+                return;
+            }
+        }
+    }
+}
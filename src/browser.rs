@@ -0,0 +1,231 @@
+use crate::{Widget, builders::WidgetData, image::ImageCache};
+use derivative::Derivative;
+use sdl2::{
+    render::TextureCreator,
+    video::WindowContext,
+    ttf::Font,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+type SelectCallback = Box<dyn Fn(&Path)>;
+
+#[derive(Debug)]
+struct Entry {
+    name: String,
+    is_dir: bool,
+}
+
+/// Contents of a tab listing files and directories, built with [`TabBuilder::browser`](crate::builders::TabBuilder::browser).
+///
+/// Press opens a directory or picks a file, Back returns to the parent directory
+/// until the starting one is reached.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct FileBrowser {
+    root: PathBuf,
+    dir: PathBuf,
+    extensions: Vec<String>,
+    show_hidden: bool,
+    #[derivative(Debug="ignore")]
+    callback: Option<SelectCallback>,
+    // None until the directory is listed, which only happens once the tab is shown
+    entries: Option<Vec<Entry>>,
+    // row to select after the next listing
+    select: usize,
+    // entry to select after the next listing instead, the directory we came from after going back
+    select_name: Option<String>,
+}
+
+// first row is the "show hidden files" toggle
+const FIRST_ENTRY: usize = 1;
+
+impl FileBrowser {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        Self {
+            root: dir.clone(),
+            dir,
+            extensions: Vec::new(),
+            show_hidden: false,
+            callback: None,
+            entries: None,
+            select: FIRST_ENTRY,
+            select_name: None,
+        }
+    }
+    /// Only lists files with one of these extensions, case-insensitive. Directories are always listed.
+    pub fn extensions<T: ToString>(mut self, extensions: impl IntoIterator<Item = T>) -> Self {
+        self.extensions = extensions.into_iter()
+            .map(|e| e.to_string().trim_start_matches('.').to_lowercase())
+            .collect();
+        self
+    }
+    pub fn show_hidden(mut self, show: bool) -> Self {
+        self.show_hidden = show;
+        self
+    }
+    /// Called with the chosen file, in addition to [`GuiEvent::FileChosen`](crate::GuiEvent::FileChosen).
+    pub fn on_select(mut self, cb: impl Fn(&Path) + 'static) -> Self {
+        self.callback = Some(Box::new(cb));
+        self
+    }
+    fn wanted(&self, name: &str, is_dir: bool) -> bool {
+        if !self.show_hidden && name.starts_with('.') {
+            return false;
+        }
+        if is_dir || self.extensions.is_empty() {
+            return true;
+        }
+        Path::new(name).extension()
+            .and_then(|e| e.to_str())
+            .map(|e| self.extensions.contains(&e.to_lowercase()))
+            .unwrap_or(false)
+    }
+    fn list(&self) -> Result<Vec<Entry>, String> {
+        let mut entries = Vec::new();
+        for dirent in fs::read_dir(&self.dir).map_err(|e| e.to_string())? {
+            let Ok(dirent) = dirent else { continue };
+            let name = dirent.file_name().to_string_lossy().into_owned();
+            // follows symlinks, so links to directories can be entered
+            let is_dir = dirent.path().is_dir();
+            if self.wanted(&name, is_dir) {
+                entries.push(Entry { name, is_dir });
+            }
+        }
+        entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+        Ok(entries)
+    }
+    /// Lists the current directory if needed, returning new rows and the row to select.
+    pub(crate) fn refresh(&mut self, font: &Font, texture_creator: &TextureCreator<WindowContext>, images: &mut ImageCache) -> Option<(Vec<Widget>, usize)> {
+        if self.entries.is_some() {
+            return None;
+        }
+        let mut rows = vec![WidgetData::toggle("Show hidden files", |_, _| {}, self.show_hidden)];
+        let entries = match self.list() {
+            Ok(entries) => entries,
+            Err(e) => {
+                rows.push(WidgetData::paragraph(format!("Cannot open {}: {e}", self.dir.display())));
+                Vec::new()
+            },
+        };
+        if let Some(name) = self.select_name.take() {
+            self.select = entries.iter()
+                .position(|e| e.name == name)
+                .map(|i| i + FIRST_ENTRY)
+                .unwrap_or(FIRST_ENTRY);
+        }
+        for entry in entries.iter() {
+            let name = if entry.is_dir { format!("{}/", entry.name) } else { entry.name.clone() };
+            rows.push(WidgetData::btn(name, |_, _| {}));
+        }
+        self.entries = Some(entries);
+        let widgets = rows.into_iter()
+            .map(|row| row.draw(font, texture_creator, images, None))
+            .collect::<Vec<_>>();
+        let select = self.select.min(widgets.len() - 1);
+        Some((widgets, select))
+    }
+    /// Handles Press on a row, returning the path of a chosen file.
+    pub(crate) fn activate(&mut self, row: usize) -> Option<PathBuf> {
+        if row < FIRST_ENTRY {
+            self.show_hidden = !self.show_hidden;
+            self.select = row;
+            self.entries = None;
+            return None;
+        }
+        let entry = self.entries.as_ref()?.get(row - FIRST_ENTRY)?;
+        let path = self.dir.join(&entry.name);
+        if entry.is_dir {
+            self.dir = path;
+            self.select = FIRST_ENTRY;
+            self.entries = None;
+            None
+        } else {
+            if let Some(cb) = &self.callback {
+                cb(&path);
+            }
+            Some(path)
+        }
+    }
+    /// Goes to the parent directory, returns false when already in the starting one.
+    pub(crate) fn back(&mut self) -> bool {
+        if self.dir == self.root {
+            return false;
+        }
+        let Some(parent) = self.dir.parent().map(Path::to_path_buf) else { return false };
+        // keep the directory we came from selected
+        self.select_name = self.dir.file_name().map(|n| n.to_string_lossy().into_owned());
+        self.select = FIRST_ENTRY;
+        self.dir = parent;
+        self.entries = None;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Tree(PathBuf);
+
+    impl Tree {
+        fn new(name: &str, dirs: &[&str], files: &[&str]) -> Self {
+            let root = std::env::temp_dir().join(format!("sgui2-test-{}-{name}", std::process::id()));
+            for dir in dirs {
+                fs::create_dir_all(root.join(dir)).expect("Failed to create test directory");
+            }
+            for file in files {
+                fs::write(root.join(file), "").expect("Failed to write test file");
+            }
+            Self(root)
+        }
+    }
+
+    impl Drop for Tree {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn names(browser: &FileBrowser) -> Vec<String> {
+        browser.list().expect("Failed to list test directory")
+            .into_iter()
+            .map(|e| if e.is_dir { format!("{}/", e.name) } else { e.name })
+            .collect()
+    }
+
+    const DIRS: [&str; 3] = ["zeta", "Alpha", ".config"];
+    const FILES: [&str; 6] = ["b.PNG", "a.txt", "C.png", ".hidden.png", "readme", "photo.jpeg"];
+
+    #[test]
+    fn lists_directories_first_by_name() {
+        let tree = Tree::new("browser-sort", &DIRS, &FILES);
+        let browser = FileBrowser::new(&tree.0);
+        assert_eq!(names(&browser), ["Alpha/", "zeta/", "a.txt", "b.PNG", "C.png", "photo.jpeg", "readme"]);
+    }
+
+    #[test]
+    fn filters_files_by_extension() {
+        let tree = Tree::new("browser-extensions", &DIRS, &FILES);
+        let browser = FileBrowser::new(&tree.0).extensions([".png", "JPEG"]);
+        assert_eq!(names(&browser), ["Alpha/", "zeta/", "b.PNG", "C.png", "photo.jpeg"]);
+        assert!(!browser.wanted("readme", false));
+        assert!(browser.wanted("readme", true));
+    }
+
+    #[test]
+    fn hides_dot_files_unless_asked() {
+        let tree = Tree::new("browser-hidden", &DIRS, &FILES);
+        let browser = FileBrowser::new(&tree.0).extensions(["png"]).show_hidden(true);
+        assert_eq!(names(&browser), [".config/", "Alpha/", "zeta/", ".hidden.png", "b.PNG", "C.png"]);
+        assert!(!FileBrowser::new(&tree.0).wanted(".config", true));
+    }
+
+    #[test]
+    fn missing_directory_is_an_error() {
+        let browser = FileBrowser::new(std::env::temp_dir().join("sgui2-test-does-not-exist"));
+        assert!(browser.list().is_err());
+    }
+}
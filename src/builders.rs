@@ -1,4 +1,6 @@
-use crate::{Gui, WidgetState, Widget, Tab, Focus, SelectionWindow, theme::Theme};
+use crate::{Gui, StatusBar, TabBarPosition, WidgetState, Widget, WidgetFlags, Tab, Focus, Layout, SelectionWindow, theme::Theme, toast::{Toasts, Notification, Notifier}, image::{ImageCache, ImageSource, Picture}, footer::Footer, settings::{self, Settings}, hints::{Glyphs, HintBar}, text::Paragraph, log::{self, LogLine, LogView}, browser::FileBrowser,
+    control::{Control, Button, Toggle, Slider, Radio}, graph::Graph, color::ColorPicker,
+    datetime::{DateTime, DateTimeFields, DateTimePicker}, ipc::ControlSocket, capture::{Binding, KeyCapture}, command::CommandBound};
 use sdl2::{
     ttf::{self, Font},
     render::{
@@ -8,20 +10,92 @@ use sdl2::{
     },
     video::WindowContext,
 };
-use std::{cell::Cell, mem, time::Instant};
+use std::{cell::Cell, collections::VecDeque, io, mem, time::Instant, path::PathBuf, process::Command, sync::mpsc::{Sender, Receiver}};
 
 pub struct GuiBuilder {
     name: String,
     tabs: Vec<Option<TabBuilder>>,
+    notify_tx: Sender<Notification>,
+    notify_rx: Option<Receiver<Notification>>,
+    tab_bar_fraction: Option<f32>,
+    tab_bar_position: TabBarPosition,
+    hint_bar: bool,
+    status_bar: Option<StatusBar>,
+    persist_to: Option<PathBuf>,
+    control_socket: Option<PathBuf>,
 }
 
 impl GuiBuilder {
     pub fn new(name: impl ToString) -> Self {
+        let (notify_tx, notify_rx) = Toasts::channel();
         Self {
             name: name.to_string(),
             tabs: Vec::new(),
+            notify_tx,
+            notify_rx: Some(notify_rx),
+            tab_bar_fraction: None,
+            tab_bar_position: TabBarPosition::Auto,
+            hint_bar: false,
+            status_bar: None,
+            persist_to: None,
+            control_socket: None,
         }
     }
+    /// Returns a handle for showing toasts, meant to be moved into widget callbacks.
+    pub fn notifier(&self) -> Notifier {
+        Notifier { tx: self.notify_tx.clone() }
+    }
+    /// Fixed width of the tab bar as a fraction of the window, instead of fitting the tab names
+    /// within [`Theme::tab_bar_min`] and [`Theme::tab_bar_max`]. Kept between 0 and 1, NaN fits the names again.
+    pub fn tab_bar_width(&mut self, fraction: f32) -> &mut Self {
+        self.tab_bar_fraction = (!fraction.is_nan()).then(|| fraction.clamp(0.0, 1.0));
+        self
+    }
+    pub fn tab_bar_position(&mut self, position: TabBarPosition) -> &mut Self {
+        self.tab_bar_position = position;
+        self
+    }
+    /// Shows the buttons for the actions available right now along the bottom of the window,
+    /// labeled for the keyboard or the kind of controller used last.
+    pub fn hint_bar(&mut self, show: bool) -> &mut Self {
+        self.hint_bar = show;
+        self
+    }
+    /// Always visible strip above the panels, see [`StatusBar`].
+    pub fn status_bar(&mut self, status_bar: StatusBar) -> &mut Self {
+        self.status_bar = Some(status_bar);
+        self
+    }
+    /// Keeps the values of widgets with an ID, see [`WidgetData::with_id`], and the last selected
+    /// tab and widget in `path`, restoring them on the next start. Failures to save are shown as error toasts.
+    pub fn persist_to(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.persist_to = Some(path.into());
+        self
+    }
+    /// Listens on a Unix domain socket at `path` for requests from other processes, one JSON object per line,
+    /// each answered with a line holding `"ok"` and either the result or an `"error"`.
+    /// An `"id"` in the request is copied into the answer.
+    ///
+    /// ```text
+    /// {"cmd": "tree"}
+    /// {"cmd": "get", "widget": "wifi"}
+    /// {"cmd": "set", "widget": [0, 2], "value": "true"}
+    /// {"cmd": "action", "key": "down"}
+    /// {"cmd": "tab", "tab": "Network"}
+    /// {"cmd": "notify", "text": "Update done", "severity": "info", "duration_ms": 3000}
+    /// {"cmd": "subscribe"}
+    /// ```
+    ///
+    /// Widgets are named by their ID or by tab and widget index, values take the form of the settings file,
+    /// see [`GuiBuilder::persist_to`]. Disabled widgets can't be set, radio buttons only to `"true"`
+    /// and sliders only within the range given to [`WidgetData::slider_with_range`].
+    /// After `subscribe` every [`GuiEvent`](crate::GuiEvent) is sent as e.g. `{"event": "quit"}`.
+    ///
+    /// A socket left behind at `path` is replaced, anything else there makes [`GuiBuilder::build`] panic.
+    pub fn control_socket(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.control_socket = Some(path.into());
+        self
+    }
     pub fn tab(&mut self, tab: &mut TabBuilder) -> &mut Self {
         self.tabs.push(Some(mem::take(tab)));
         self
@@ -36,6 +110,7 @@ impl GuiBuilder {
         // sdl init
         let sdl = sdl2::init().expect("Failed to initialize SDL");
         let video = sdl.video().expect("Failed to initialize SDL video");
+        let game_controller = sdl.game_controller().expect("Failed to initialize SDL game controller support");
         let window = video.window(&self.name, 1280, 800)
             .allow_highdpi()
             .resizable()
@@ -54,23 +129,40 @@ impl GuiBuilder {
         let fontsize = (20.0 * (dpi/72.0)).clamp(10.0, 100.0) as u16;
 
         // init our font rendering
-        let ttf = ttf::init().expect("Failed to initialize SDL_ttf");
+        // leaked so the font can live inside Gui for rendering text at runtime
+        let ttf: &'static ttf::Sdl2TtfContext = Box::leak(Box::new(ttf::init().expect("Failed to initialize SDL_ttf")));
         let font = ttf.load_font(Theme::font(), fontsize).expect("Failed to load font");
         
+        let settings = self.persist_to.take().map(Settings::load);
+        let control_socket = self.control_socket.take()
+            .map(|path| ControlSocket::bind(path).expect("Failed to bind control socket"));
+        let mut images = ImageCache::new();
         let mut built_tabs = Vec::new();
         for pre_tab_maybe in self.tabs.iter_mut() {
             if let Some(pre_tab) = pre_tab_maybe.take() {
-                built_tabs.push(Some(pre_tab.build(&font, &texture_creator)));
+                built_tabs.push(Some(pre_tab.build(&font, &texture_creator, &mut images, settings.as_ref())));
             } else {
                 built_tabs.push(None);
             }
         }
         
+        let notify_rx = self.notify_rx.take().expect("GuiBuilder::build can only be called once");
+
+        // the file might be from an older version of the program with other tabs
+        let restored = |key| settings.as_ref().and_then(|s| s.get(key)).and_then(|v| v.parse::<usize>().ok());
+        let current_tab = restored("sgui2.tab")
+            .filter(|i| built_tabs.get(*i).is_some_and(Option::is_some))
+            .unwrap_or(0);
+        let current_widget = restored("sgui2.widget")
+            .filter(|i| built_tabs[current_tab].as_ref().is_some_and(|t| *i < t.widgets.len()))
+            .unwrap_or(0);
+
         Gui {
             canvas,
+            texture_creator,
             tabs: built_tabs,
-            current_tab: 0,
-            current_widget: 0,
+            current_tab,
+            current_widget,
             font_height: font.height(),
             event_pump: sdl.event_pump().unwrap(),
             window_size: (1280, 800),
@@ -80,11 +172,36 @@ impl GuiBuilder {
             selection: SelectionWindow::new((255, 255, 255)),
             want_widget_scroll: 0,
             widget_scroll: 0,
+            want_tab_bar_scroll: 0,
+            tab_bar_scroll: 0,
+            widget_rows: Vec::new(),
+            page_height: 0,
+            tab_starts: Vec::new(),
+            tab_page: 0,
+            row_offset: 0,
+            row_offset_of: (0, 0),
+            selected_height: 0,
+            toasts: Toasts::new(self.notify_tx.clone(), notify_rx),
+            font,
+            images,
+            game_controller,
+            controllers: Vec::new(),
+            tab_bar_fraction: self.tab_bar_fraction,
+            tab_bar_position: self.tab_bar_position,
+            footer: Footer::default(),
+            hint_bar: self.hint_bar.then(HintBar::default),
+            glyphs: Glyphs::default(),
+            status_bar: self.status_bar.take(),
+            settings,
+            control_socket,
+            injected: VecDeque::new(),
         }
     }
 }
 
 fn draw_text(input: &str, font: &Font, texture_creator: &TextureCreator<WindowContext>) -> Texture {
+    // SDL_ttf refuses to render zero width text
+    let input = if input.is_empty() { " " } else { input };
     let surface = font.render(input).blended((255, 255, 255)).expect("Failed to render text");
     let texture = texture_creator.create_texture_from_surface(&surface).expect("Failed to create texture from surface");
     texture
@@ -93,68 +210,259 @@ fn draw_text(input: &str, font: &Font, texture_creator: &TextureCreator<WindowCo
 #[derive(Default)]
 pub struct TabBuilder {
     name: String,
+    icon: Option<ImageSource>,
     widgets: Vec<WidgetData>,
+    radio_groups: Vec<Box<dyn Fn(usize)>>,
+    radio_ids: Vec<Option<String>>,
+    browser: Option<FileBrowser>,
+    layout: Layout,
+    description: Option<String>,
 }
 
 impl TabBuilder {
     pub fn new(name: impl ToString) -> Self {
         TabBuilder {
             name: name.to_string(),
+            icon: None,
             widgets: Vec::new(),
+            radio_groups: Vec::new(),
+            radio_ids: Vec::new(),
+            browser: None,
+            layout: Layout::List,
+            description: None,
         }
     }
+    /// Arranges widgets as tiles, see [`WidgetData::tile`].
+    pub fn grid(&mut self) -> &mut Self {
+        self.layout = Layout::Grid;
+        self
+    }
+    /// Turns this tab into a file browser, its rows are generated from the directory listing.
+    pub fn browser(&mut self, browser: FileBrowser) -> &mut Self {
+        self.browser = Some(browser);
+        self
+    }
+    /// Shows an icon next to the tab name, scaled to the font height.
+    pub fn icon(&mut self, source: impl Into<ImageSource>) -> &mut Self {
+        self.icon = Some(source.into());
+        self
+    }
+    /// Shown at the bottom of the window while the tab is selected in the tab bar.
+    pub fn description(&mut self, text: impl ToString) -> &mut Self {
+        self.description = Some(text.to_string());
+        self
+    }
     pub fn widget(&mut self, data: WidgetData) -> &mut Self {
         self.widgets.push(data);
         self
     }
-    fn build(self, font: &Font, texture_creator: &TextureCreator<WindowContext>) -> Tab {
+    /// Adds one row per option, only one of which can be selected at a time.
+    /// `cb` receives the index of the newly selected option.
+    pub fn radio_group<T: ToString>(&mut self, options: impl IntoIterator<Item = T>, selected: usize, cb: impl Fn(usize) + 'static) -> &mut Self {
+        let group = self.radio_groups.len();
+        for (i, name) in options.into_iter().enumerate() {
+            let mut data = WidgetData::new(name, Radio::new(i == selected));
+            data.group = Some(group);
+            self.widgets.push(data);
+        }
+        self.radio_groups.push(Box::new(cb));
+        self.radio_ids.push(None);
+        self
+    }
+    /// Like [`TabBuilder::radio_group`], with the index of the selected option saved under `id`
+    /// with [`GuiBuilder::persist_to`]. `cb` receives a restored selection once the Gui is built.
+    pub fn radio_group_with_id<T: ToString>(&mut self, id: impl ToString, options: impl IntoIterator<Item = T>, selected: usize, cb: impl Fn(usize) + 'static) -> &mut Self {
+        self.radio_group(options, selected, cb);
+        *self.radio_ids.last_mut().expect("pushed by radio_group") = Some(id.to_string());
+        self
+    }
+    fn build(self, font: &Font, texture_creator: &TextureCreator<WindowContext>, images: &mut ImageCache, settings: Option<&Settings>) -> Tab {
         let text = draw_text(&self.name, font, texture_creator);
         let mut new_widgets = Vec::new();
         for widget in self.widgets.into_iter() {
-            new_widgets.push(widget.draw(font, texture_creator));
+            new_widgets.push(widget.draw(font, texture_creator, images, settings));
         }
-        Tab {
+        let mut tab = Tab {
+            name: self.name,
             text,
+            icon: self.icon.map(|source| images.load(source, texture_creator)),
             widgets: new_widgets,
+            radio_groups: self.radio_groups,
+            radio_ids: self.radio_ids,
+            browser: self.browser,
+            layout: self.layout,
+            columns: 1,
+            description: self.description,
+        };
+        if let Some(settings) = settings {
+            tab.restore_radios(settings);
         }
+        tab
     }
 }
 
 pub struct WidgetData {
     name: String,
-    callback: Option<Box<dyn Fn(&mut WidgetState, &Cell<Instant>)>>,
-    w_type: WidgetState,
+    kind: WidgetKind,
+    group: Option<usize>,
+    icon: Option<ImageSource>,
+    flags: WidgetFlags,
+    description: Option<String>,
+    id: Option<String>,
+}
+
+enum WidgetKind {
+    Control(Box<dyn Control>),
+    // decoded only once the Gui is built
+    Image(ImageSource, Option<(u32, u32)>),
 }
 
 impl WidgetData {
-    pub fn btn(name: impl ToString, cb: impl Fn(&mut WidgetState, &Cell<Instant>) + 'static) -> Self {
+    fn new(name: impl ToString, control: impl Control + 'static) -> Self {
         Self {
             name: name.to_string(),
-            callback: Some(Box::new(cb)),
-            w_type: WidgetState::Button,
+            kind: WidgetKind::Control(Box::new(control)),
+            group: None,
+            icon: None,
+            flags: WidgetFlags::default(),
+            description: None,
+            id: None,
         }
     }
+    pub fn btn(name: impl ToString, cb: impl Fn(&mut WidgetState, &Cell<Instant>) + 'static) -> Self {
+        Self::new(name, Button::new(Some(Box::new(cb))))
+    }
     pub fn toggle(name: impl ToString, cb: impl Fn(&mut WidgetState, &Cell<Instant>) + 'static, state: bool) -> Self {
-        Self {
-            name: name.to_string(),
-            callback: Some(Box::new(cb)),
-            w_type: WidgetState::Toggle(state, if state { 255 } else { 0 }),
-        }
+        Self::new(name, Toggle::new(Some(Box::new(cb)), state))
     }
     pub fn slider(name: impl ToString, cb: impl Fn(&mut WidgetState, &Cell<Instant>) + 'static, state: u8) -> Self {
+        Self::new(name, Slider::new(Some(Box::new(cb)), state))
+    }
+    /// Slider kept between `min` and `max`, values from outside that aren't are refused.
+    pub fn slider_with_range(name: impl ToString, cb: impl Fn(&mut WidgetState, &Cell<Instant>) + 'static, state: u8, min: u8, max: u8) -> Self {
+        Self::new(name, Slider::new(Some(Box::new(cb)), state).with_range(min, max))
+    }
+    /// Toggle that runs `set` through `sh -c` whenever it is changed, with `{}` replaced by `true` or `false`.
+    /// `get` is run once at the start and its output, like `1`, `on` or `enabled`, becomes the initial state.
+    /// Commands run in the background while the row shows a busy bar, failures are shown as toasts and undo the change.
+    /// Commands taking longer than [`Theme::command_timeout`](crate::Theme::command_timeout) are killed and count as failed.
+    pub fn toggle_command(name: impl ToString, set: impl ToString, get: Option<&str>, state: bool) -> Self {
+        let control = CommandBound::new(Box::new(Toggle::new(None, state)), set.to_string(), get.map(str::to_string));
+        Self::new(name, control)
+    }
+    /// Like [`WidgetData::toggle_command`] for a slider, `{}` is replaced by the value from 0 to 255
+    /// and `get` has to print a number in that range.
+    pub fn slider_command(name: impl ToString, set: impl ToString, get: Option<&str>, state: u8) -> Self {
+        let control = CommandBound::new(Box::new(Slider::new(None, state)), set.to_string(), get.map(str::to_string));
+        Self::new(name, control)
+    }
+    /// Color swatch, Press opens an editor with hue/saturation/value bars and presets.
+    /// The callback gets [`WidgetState::Color`] with the picked RGB value.
+    pub fn color(name: impl ToString, cb: impl Fn(&mut WidgetState, &Cell<Instant>) + 'static, rgb: (u8, u8, u8)) -> Self {
+        Self::new(name, ColorPicker::new(Some(Box::new(cb)), rgb))
+    }
+    /// Date and/or time, Press enters editing where Left/Right pick a field and Up/Down change it.
+    /// The callback gets [`WidgetState::DateTime`].
+    pub fn date_time(name: impl ToString, cb: impl Fn(&mut WidgetState, &Cell<Instant>) + 'static, value: DateTime, fields: DateTimeFields, twelve_hour: bool) -> Self {
+        Self::new(name, DateTimePicker::new(Some(Box::new(cb)), value, fields, twelve_hour))
+    }
+    /// Shows a key or controller input, Press waits for a new one. Holding Back cancels.
+    /// The callback gets [`WidgetState::Binding`] once something was captured.
+    pub fn key_capture(name: impl ToString, cb: impl Fn(&mut WidgetState, &Cell<Instant>) + 'static, binding: Option<Binding>) -> Self {
+        Self::new(name, KeyCapture::new(Some(Box::new(cb)), binding))
+    }
+    /// Widget of a kind defined outside of sgui2.
+    pub fn custom(name: impl ToString, control: impl Control + 'static) -> Self {
+        Self::new(name, control)
+    }
+    /// Chart of samples pushed through the [`GraphFeed`](crate::GraphFeed) returned by [`Graph::new`].
+    pub fn graph(name: impl ToString, graph: Graph) -> Self {
+        Self::new(name, graph)
+    }
+    /// Block of text word-wrapped to the width of the panel, `\n` starts a new line.
+    /// Text taller than the panel is scrolled through with Up and Down while selected.
+    pub fn paragraph(text: impl ToString) -> Self {
+        Self::new("", Paragraph::new(text.to_string()))
+    }
+    /// Scrollable view of the last `capacity` lines received from `rx`.
+    /// Follows new lines unless scrolled up, Press enters the view to scroll with Up/Down.
+    pub fn log(name: impl ToString, rx: Receiver<LogLine>, capacity: usize) -> Self {
+        Self::new(name, LogView::new(rx, capacity))
+    }
+    /// Like [`WidgetData::log`], fed from stdout and stderr of `cmd`, which is spawned immediately
+    /// and killed when the widget is dropped.
+    pub fn log_command(name: impl ToString, cmd: &mut Command, capacity: usize) -> io::Result<Self> {
+        let (rx, child) = log::spawn(cmd)?;
+        Ok(Self::new(name, LogView::new(rx, capacity).with_child(child)))
+    }
+    /// Standalone image, drawn at `size` or at its natural size if `None`.
+    pub fn image(source: impl Into<ImageSource>, size: Option<(u32, u32)>) -> Self {
         Self {
-            name: name.to_string(),
-            callback: Some(Box::new(cb)),
-            w_type: WidgetState::Slider(state, state),
+            name: String::new(),
+            kind: WidgetKind::Image(source.into(), size),
+            group: None,
+            icon: None,
+            flags: WidgetFlags::default(),
+            description: None,
+            id: None,
         }
     }
-    fn draw(self, font: &Font, texture_creator: &TextureCreator<WindowContext>) -> Widget {
+    /// Button for tabs with [`TabBuilder::grid`] layout, drawn as an icon with a caption below.
+    pub fn tile(name: impl ToString, icon: impl Into<ImageSource>, cb: impl Fn(&mut WidgetState, &Cell<Instant>) + 'static) -> Self {
+        Self::btn(name, cb).with_icon(icon)
+    }
+    /// Adds a thumbnail in front of the label, scaled to the font height.
+    pub fn with_icon(mut self, source: impl Into<ImageSource>) -> Self {
+        self.icon = Some(source.into());
+        self
+    }
+    /// Name the value of the widget is saved under with [`GuiBuilder::persist_to`], unique across all tabs.
+    pub fn with_id(mut self, id: impl ToString) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+    /// Explanation shown at the bottom of the window while the widget is selected.
+    pub fn with_description(mut self, text: impl ToString) -> Self {
+        self.description = Some(text.to_string());
+        self
+    }
+    /// Dimmed and ignoring input until enabled through [`WidgetData::flags`].
+    pub fn disabled(self) -> Self {
+        self.flags.set_enabled(false);
+        self
+    }
+    /// Takes no space until shown through [`WidgetData::flags`].
+    pub fn hidden(self) -> Self {
+        self.flags.set_visible(false);
+        self
+    }
+    /// Returns a handle for enabling or showing the widget later, meant to be moved into callbacks.
+    pub fn flags(&self) -> WidgetFlags {
+        self.flags.clone()
+    }
+    pub(crate) fn draw(self, font: &Font, texture_creator: &TextureCreator<WindowContext>, images: &mut ImageCache, settings: Option<&Settings>) -> Widget {
+        let mut control: Box<dyn Control> = match self.kind {
+            WidgetKind::Control(control) => control,
+            WidgetKind::Image(source, size) => Box::new(Picture::new(images.load(source, texture_creator), size)),
+        };
+        // callbacks are first run on the first frame, so they already see the restored value
+        let saved = self.id.as_deref().zip(settings).and_then(|(id, settings)| settings.get(id));
+        if let (Some(saved), Some(state)) = (saved, control.state()) {
+            // values the widget no longer takes, like after its range changed, keep the default
+            let mut restored = state.clone();
+            if settings::decode(&mut restored, saved) && control.accepts(&restored) {
+                *control.state().expect("checked above") = restored;
+            }
+        }
         Widget {
-            text: draw_text(&self.name, font, texture_creator),
-            callback: self.callback,
-            // make sure that function used by users is kickstarted
-            next_callback: Cell::new(Instant::now()),
-            state: self.w_type,
+            text: (!self.name.is_empty()).then(|| draw_text(&self.name, font, texture_creator)),
+            name: self.name,
+            control,
+            group: self.group,
+            icon: self.icon.map(|source| images.load(source, texture_creator)),
+            flags: self.flags,
+            description: self.description,
+            id: self.id,
         }
     }
 }
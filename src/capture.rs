@@ -0,0 +1,168 @@
+use crate::{WidgetState, destroy_texture, theme::Theme, control::{Callback, Context, Control, WidgetCallback}};
+use sdl2::{
+    controller::{Axis, Button},
+    event::Event,
+    keyboard::Keycode,
+    rect::Rect,
+    render::Texture,
+};
+use std::time::Instant;
+
+// past this, stick movement counts as a press
+const AXIS_THRESHOLD: i16 = 16000;
+
+/// Keyboard key, controller button or stick direction, as captured by key capture widgets.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Binding {
+    Key(Keycode),
+    Button(Button),
+    /// Axis and whether it was pushed towards positive values.
+    Axis(Axis, bool),
+}
+
+impl Binding {
+    pub fn name(&self) -> String {
+        match self {
+            Binding::Key(key) => key.name(),
+            Binding::Button(button) => button.string(),
+            Binding::Axis(axis, positive) => format!("{}{}", axis.string(), if *positive { "+" } else { "-" }),
+        }
+    }
+    fn is_back(&self) -> bool {
+        matches!(self, Binding::Key(Keycode::Escape) | Binding::Button(Button::B))
+    }
+}
+
+pub(crate) struct KeyCapture {
+    state: WidgetState,
+    callback: Callback,
+    // Some while waiting for input
+    listening: Option<Instant>,
+    // Back can be bound too, so it only cancels when held
+    back_held: Option<(Binding, Instant)>,
+    done: bool,
+    text: Option<(String, Texture)>,
+}
+
+impl KeyCapture {
+    pub(crate) fn new(cb: Option<WidgetCallback>, binding: Option<Binding>) -> Self {
+        Self {
+            state: WidgetState::Binding(binding),
+            callback: Callback::new(cb),
+            listening: None,
+            back_held: None,
+            done: false,
+            text: None,
+        }
+    }
+    fn finish(&mut self, binding: Option<Binding>) {
+        if let Some(binding) = binding {
+            self.state = WidgetState::Binding(Some(binding));
+            self.callback.call(&mut self.state);
+        }
+        self.listening = None;
+        self.back_held = None;
+        self.done = true;
+    }
+    fn label(&self) -> String {
+        if let Some(started) = self.listening {
+            if self.back_held.is_some() {
+                return "Keep holding to cancel".to_string();
+            }
+            let left = Theme::capture_timeout().saturating_sub(started.elapsed());
+            return format!("Press a key... {}", left.as_secs() + 1);
+        }
+        match self.state {
+            WidgetState::Binding(Some(binding)) => binding.name(),
+            _ => "None".to_string(),
+        }
+    }
+}
+
+impl Control for KeyCapture {
+    fn draw(&mut self, ctx: &mut Context) {
+        if let Some(started) = self.listening {
+            let cancelled = match self.back_held {
+                Some((_, since)) => since.elapsed() > Theme::capture_cancel_hold(),
+                None => started.elapsed() > Theme::capture_timeout(),
+            };
+            if cancelled {
+                self.finish(None);
+            }
+        }
+
+        let text = self.label();
+        if self.text.as_ref().map(|(t, _)| *t != text).unwrap_or(true) {
+            let surface = ctx.font.render(&text).blended(Theme::fg_widgets()).expect("Failed to render text");
+            let texture = ctx.texture_creator.create_texture_from_surface(&surface)
+                .expect("Failed to create texture from surface");
+            if let Some((_, old)) = self.text.replace((text, texture)) {
+                destroy_texture(old);
+            }
+        }
+        if let Some((_, texture)) = &self.text {
+            let q = texture.query();
+            let x = ctx.canvas.viewport().width() as i32 - q.width as i32 - ctx.label.height() as i32 / 2;
+            ctx.canvas.copy(texture, None, Rect::new(x, 0, q.width, q.height)).expect("Failed to draw key capture widget");
+        }
+        self.callback.tick(&mut self.state);
+    }
+    fn grabs_input(&self) -> bool {
+        true
+    }
+    fn hints(&self, back: &'static str) -> Vec<(&'static str, &'static str)> {
+        vec![("Any input", "Bind"), (back, "Hold to cancel")]
+    }
+    fn set_editing(&mut self, editing: bool) {
+        self.listening = editing.then(Instant::now);
+        self.back_held = None;
+        self.done = false;
+    }
+    fn raw_event(&mut self, event: &Event) -> bool {
+        if self.listening.is_none() {
+            return false;
+        }
+        let pressed = match *event {
+            Event::KeyDown { keycode: Some(key), repeat: false, .. } => Some(Binding::Key(key)),
+            Event::ControllerButtonDown { button, .. } => Some(Binding::Button(button)),
+            Event::ControllerAxisMotion { axis, value, .. } if value.unsigned_abs() > AXIS_THRESHOLD as u16 => Some(Binding::Axis(axis, value > 0)),
+            Event::KeyUp { keycode: Some(key), .. } => {
+                // released before the hold timeout, so Back itself is being bound
+                if let Some((binding, _)) = self.back_held {
+                    if binding == Binding::Key(key) {
+                        self.finish(Some(binding));
+                    }
+                }
+                return true;
+            },
+            Event::ControllerButtonUp { button, .. } => {
+                if let Some((binding, _)) = self.back_held {
+                    if binding == Binding::Button(button) {
+                        self.finish(Some(binding));
+                    }
+                }
+                return true;
+            },
+            // everything else, like window events, is handled as usual
+            Event::KeyDown { .. } | Event::ControllerAxisMotion { .. } => return true,
+            _ => return false,
+        };
+        if let Some(binding) = pressed {
+            if binding.is_back() {
+                self.back_held = Some((binding, Instant::now()));
+            } else if self.back_held.is_none() {
+                self.finish(Some(binding));
+            }
+        }
+        true
+    }
+    fn editing_done(&mut self) -> bool {
+        std::mem::take(&mut self.done)
+    }
+    fn state(&mut self) -> Option<&mut WidgetState> {
+        Some(&mut self.state)
+    }
+    fn state_changed(&mut self) {
+        self.callback.call(&mut self.state);
+    }
+}
@@ -0,0 +1,250 @@
+use crate::{ActionKey, WidgetState, theme::Theme, control::{Callback, Context, Control, WidgetCallback}};
+use sdl2::rect::Rect;
+
+/// `hue` in degrees, the rest 0-255.
+pub(crate) fn hsv_to_rgb(hue: u16, sat: u8, val: u8) -> (u8, u8, u8) {
+    let h = (hue % 360) as f32 / 60.0;
+    let s = sat as f32 / 255.0;
+    let v = val as f32 / 255.0;
+    let c = v * s;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = v - c;
+    let to_u8 = |f: f32| ((f + m) * 255.0).round() as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+pub(crate) fn rgb_to_hsv((r, g, b): (u8, u8, u8)) -> (u16, u8, u8) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = (max - min) as f32;
+    if max == 0 || delta == 0.0 {
+        return (0, 0, max);
+    }
+    let (r, g, b) = (r as f32, g as f32, b as f32);
+    let hue = if max as f32 == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max as f32 == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    (hue.round() as u16 % 360, (delta / max as f32 * 255.0).round() as u8, max)
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Field {
+    Hue,
+    Saturation,
+    Value,
+    Palette,
+}
+
+/// Swatch that opens a hue/saturation/value editor with a palette of presets on Press.
+pub(crate) struct ColorPicker {
+    state: WidgetState,
+    callback: Callback,
+    hsv: (u16, u8, u8),
+    // rgb the hsv above was derived from, callbacks may change the state behind our back
+    synced: (u8, u8, u8),
+    editing: bool,
+    field: Field,
+    preset: usize,
+}
+
+impl ColorPicker {
+    pub(crate) fn new(cb: Option<WidgetCallback>, (r, g, b): (u8, u8, u8)) -> Self {
+        Self {
+            state: WidgetState::Color(r, g, b),
+            callback: Callback::new(cb),
+            hsv: rgb_to_hsv((r, g, b)),
+            synced: (r, g, b),
+            editing: false,
+            field: Field::Hue,
+            preset: 0,
+        }
+    }
+    fn sync(&mut self) {
+        if let WidgetState::Color(r, g, b) = self.state {
+            if (r, g, b) != self.synced {
+                self.hsv = rgb_to_hsv((r, g, b));
+                self.synced = (r, g, b);
+            }
+        }
+    }
+    fn set_rgb(&mut self, rgb: (u8, u8, u8)) {
+        self.state = WidgetState::Color(rgb.0, rgb.1, rgb.2);
+        self.synced = rgb;
+        self.callback.call(&mut self.state);
+    }
+    fn draw_bar(ctx: &mut Context, area: Rect, color_at: impl Fn(u8) -> (u8, u8, u8), marker: u8) {
+        for x in 0..area.width() as i32 {
+            let t = (x * 255 / (area.width() as i32 - 1).max(1)) as u8;
+            ctx.canvas.set_draw_color(color_at(t));
+            ctx.canvas.draw_line((area.x() + x, area.y()), (area.x() + x, area.bottom() - 1))
+                .expect("Failed to draw color widget");
+        }
+        ctx.canvas.set_draw_color(Theme::fg_widgets());
+        let mx = area.x() + marker as i32 * (area.width() as i32 - 1) / 255;
+        ctx.canvas.draw_rect(Rect::new(mx - 2, area.y() - 2, 5, area.height() + 4))
+            .expect("Failed to draw color widget");
+    }
+}
+
+impl Control for ColorPicker {
+    fn measure(&mut self, ctx: &mut Context) -> u32 {
+        if self.editing {
+            // one row per field
+            ctx.label.height() * 5
+        } else {
+            ctx.label.height()
+        }
+    }
+    fn draw(&mut self, ctx: &mut Context) {
+        self.sync();
+        let bounds = ctx.canvas.viewport();
+        let line = ctx.label.height();
+        let margin = line/4;
+        let box_size = line - margin*2;
+        let old = ctx.canvas.draw_color();
+
+        let swatch = Rect::new((bounds.width() - (margin*2 + box_size*2)) as i32, margin as i32, box_size*2, box_size);
+        if let WidgetState::Color(r, g, b) = self.state {
+            ctx.canvas.set_draw_color((r, g, b));
+            ctx.canvas.fill_rect(swatch).expect("Failed to draw color widget");
+        }
+        ctx.canvas.set_draw_color(old);
+        ctx.canvas.draw_rect(swatch).expect("Failed to draw color widget");
+
+        if self.editing {
+            let (h, s, v) = self.hsv;
+            let width = bounds.width() - margin*4;
+            let row = |i: u32| Rect::new(margin as i32, (line * i + margin) as i32, width, box_size);
+            Self::draw_bar(ctx, row(1), |t| hsv_to_rgb(t as u16 * 360 / 256, 255, 255), (h as u32 * 255 / 359) as u8);
+            Self::draw_bar(ctx, row(2), |t| hsv_to_rgb(h, t, v.max(64)), s);
+            Self::draw_bar(ctx, row(3), |t| hsv_to_rgb(h, s, t), v);
+
+            let presets = Theme::color_presets();
+            let cell = width / presets.len() as u32;
+            for (i, (r, g, b)) in presets.iter().enumerate() {
+                let rect = Rect::new(margin as i32 + (i as u32 * cell) as i32, (line * 4 + margin) as i32, cell - margin, box_size);
+                ctx.canvas.set_draw_color((*r, *g, *b));
+                ctx.canvas.fill_rect(rect).expect("Failed to draw color widget");
+                if self.field == Field::Palette && i == self.preset {
+                    ctx.canvas.set_draw_color(Theme::fg_widgets());
+                    ctx.canvas.draw_rect(Rect::new(rect.x() - 2, rect.y() - 2, rect.width() + 4, rect.height() + 4))
+                        .expect("Failed to draw color widget");
+                }
+            }
+
+            let current = match self.field {
+                Field::Hue => 1,
+                Field::Saturation => 2,
+                Field::Value => 3,
+                Field::Palette => 4,
+            };
+            ctx.canvas.set_draw_color(old);
+            ctx.canvas.draw_rect(Rect::new(0, (line * current) as i32, bounds.width() - margin*2, line))
+                .expect("Failed to draw color widget");
+        }
+        ctx.canvas.set_draw_color(old);
+        self.callback.tick(&mut self.state);
+    }
+    fn process_action(&mut self, action: &ActionKey) {
+        if !self.editing {
+            return;
+        }
+        self.sync();
+        let (h, s, v) = self.hsv;
+        match (action, self.field) {
+            (ActionKey::Up, Field::Saturation) => self.field = Field::Hue,
+            (ActionKey::Up, Field::Value) => self.field = Field::Saturation,
+            (ActionKey::Up, Field::Palette) => self.field = Field::Value,
+            (ActionKey::Down, Field::Hue) => self.field = Field::Saturation,
+            (ActionKey::Down, Field::Saturation) => self.field = Field::Value,
+            (ActionKey::Down, Field::Value) => self.field = Field::Palette,
+            (ActionKey::Left | ActionKey::Right, field) => {
+                let up = *action == ActionKey::Right;
+                let step = |x: u8| if up { x.saturating_add(16) } else { x.saturating_sub(16) };
+                match field {
+                    Field::Hue => self.hsv.0 = if up { (h + 10) % 360 } else { (h + 350) % 360 },
+                    Field::Saturation => self.hsv.1 = step(s),
+                    Field::Value => self.hsv.2 = step(v),
+                    Field::Palette => {
+                        let count = Theme::color_presets().len();
+                        self.preset = if up { (self.preset + 1) % count } else { (self.preset + count - 1) % count };
+                        let rgb = Theme::color_presets()[self.preset];
+                        self.hsv = rgb_to_hsv(rgb);
+                        self.set_rgb(rgb);
+                        return;
+                    },
+                }
+                let (h, s, v) = self.hsv;
+                self.set_rgb(hsv_to_rgb(h, s, v));
+            },
+            _ => (),
+        }
+    }
+    fn grabs_input(&self) -> bool {
+        true
+    }
+    fn hints(&self, back: &'static str) -> Vec<(&'static str, &'static str)> {
+        vec![("\u{2191}\u{2193}", "Field"), ("\u{2190}\u{2192}", "Change"), (back, "Done")]
+    }
+    fn set_editing(&mut self, editing: bool) {
+        self.editing = editing;
+    }
+    fn state(&mut self) -> Option<&mut WidgetState> {
+        Some(&mut self.state)
+    }
+    fn state_changed(&mut self) {
+        self.callback.call(&mut self.state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primaries() {
+        assert_eq!(hsv_to_rgb(0, 255, 255), (255, 0, 0));
+        assert_eq!(hsv_to_rgb(120, 255, 255), (0, 255, 0));
+        assert_eq!(hsv_to_rgb(240, 255, 255), (0, 0, 255));
+        assert_eq!(rgb_to_hsv((255, 255, 0)), (60, 255, 255));
+        assert_eq!(rgb_to_hsv((255, 0, 255)), (300, 255, 255));
+    }
+
+    #[test]
+    fn hue_wraps_around() {
+        assert_eq!(hsv_to_rgb(360, 255, 255), hsv_to_rgb(0, 255, 255));
+    }
+
+    #[test]
+    fn grays_have_no_hue_or_saturation() {
+        assert_eq!(rgb_to_hsv((0, 0, 0)), (0, 0, 0));
+        assert_eq!(rgb_to_hsv((128, 128, 128)), (0, 0, 128));
+        assert_eq!(hsv_to_rgb(200, 0, 128), (128, 128, 128));
+    }
+
+    #[test]
+    fn round_trip() {
+        for hue in (0..360).step_by(7) {
+            let (h, s, v) = rgb_to_hsv(hsv_to_rgb(hue, 255, 255));
+            assert!(h.abs_diff(hue) <= 1, "hue {hue} came back as {h}");
+            assert_eq!((s, v), (255, 255));
+        }
+        for rgb in [(12, 200, 99), (250, 3, 40), (1, 2, 3), (90, 90, 200)] {
+            let (h, s, v) = rgb_to_hsv(rgb);
+            let (r, g, b) = hsv_to_rgb(h, s, v);
+            assert!(r.abs_diff(rgb.0) <= 2 && g.abs_diff(rgb.1) <= 2 && b.abs_diff(rgb.2) <= 2, "{rgb:?} came back as {:?}", (r, g, b));
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use crate::{ActionKey, WidgetState, settings, theme::Theme, control::{Context, Control}};
+use sdl2::rect::Rect;
+use sdl2::event::Event;
+use std::io::{self, Read};
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+enum Job {
+    Get,
+    /// Carries the value being applied.
+    Set(String),
+}
+
+/// Command run through `sh -c`, waited for without blocking the Gui.
+struct Running {
+    cmd: String,
+    child: Child,
+    // read on their own threads, so a full pipe can't stall the command
+    stdout: JoinHandle<String>,
+    stderr: JoinHandle<String>,
+    status: Option<io::Result<ExitStatus>>,
+}
+
+impl Running {
+    fn start(cmd: String) -> Result<Self, String> {
+        let mut child = Command::new("sh").arg("-c").arg(&cmd)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // own process group, so killing it also gets whatever the shell started
+            .process_group(0)
+            .spawn()
+            .map_err(|e| format!("Failed to run `{cmd}`: {e}"))?;
+        let stdout = read_all(child.stdout.take());
+        let stderr = read_all(child.stderr.take());
+        Ok(Self { cmd, child, stdout, stderr, status: None })
+    }
+    /// Whether the command exited and all of its output was read.
+    fn done(&mut self) -> bool {
+        if self.status.is_none() {
+            self.status = self.child.try_wait().transpose();
+        }
+        self.status.is_some() && self.stdout.is_finished() && self.stderr.is_finished()
+    }
+    fn output(self) -> Result<String, String> {
+        let status = self.status.expect("only called once done")
+            .map_err(|e| format!("Failed to wait for `{}`: {e}", self.cmd))?;
+        let stdout = self.stdout.join().unwrap_or_default();
+        let stderr = self.stderr.join().unwrap_or_default();
+        outcome(&self.cmd, status, &stdout, &stderr)
+    }
+    fn kill(&mut self) {
+        if self.status.is_none() {
+            // SAFETY: kill only sends a signal, the group is the child's own
+            unsafe { libc::kill(-(self.child.id() as i32), libc::SIGKILL) };
+            let _ = self.child.wait();
+        }
+    }
+}
+
+fn read_all(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<String> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+/// Built-in control whose value is read from and applied through shell commands,
+/// which run in the background while the row shows a busy bar.
+pub(crate) struct CommandBound {
+    inner: Box<dyn Control>,
+    /// `{}` is replaced by the new value.
+    set: String,
+    // value the system is known to have
+    applied: Option<String>,
+    running: Option<(Job, Running)>,
+    error: Option<String>,
+    started: Instant,
+    timeout: Duration,
+}
+
+impl CommandBound {
+    pub(crate) fn new(mut inner: Box<dyn Control>, set: String, get: Option<String>) -> Self {
+        let applied = inner.state().and_then(|state| settings::encode(state));
+        let mut bound = Self {
+            inner,
+            set,
+            applied,
+            running: None,
+            error: None,
+            started: Instant::now(),
+            timeout: Theme::command_timeout(),
+        };
+        if let Some(get) = get {
+            bound.run(Job::Get, get);
+        }
+        bound
+    }
+    fn run(&mut self, job: Job, cmd: String) {
+        match Running::start(cmd) {
+            Ok(running) => {
+                self.running = Some((job, running));
+                self.started = Instant::now();
+            },
+            Err(e) => self.finish(job, Err(e)),
+        }
+    }
+    fn current(&mut self) -> Option<String> {
+        self.inner.state().and_then(|state| settings::encode(state))
+    }
+    fn finish(&mut self, job: Job, result: Result<String, String>) {
+        match (job, result) {
+            (Job::Get, Ok(output)) => {
+                let Some(state) = self.inner.state() else { return };
+                let text = normalize(state, output.trim());
+                if settings::decode(state, text) {
+                    self.applied = settings::encode(state);
+                } else {
+                    self.error = Some(format!("Unexpected output \"{}\"", output.trim()));
+                }
+            },
+            (Job::Set(value), Ok(_)) => self.applied = Some(value),
+            (_, Err(e)) => {
+                // back to what the system still has
+                if let (Some(state), Some(applied)) = (self.inner.state(), &self.applied) {
+                    settings::decode(state, applied);
+                }
+                self.error = Some(e);
+            },
+        }
+    }
+}
+
+/// Accepts the usual spellings of on and off for toggles, other text is passed on as is.
+fn normalize<'a>(state: &WidgetState, text: &'a str) -> &'a str {
+    if !matches!(state, WidgetState::Toggle(..)) {
+        return text;
+    }
+    match text.to_lowercase().as_str() {
+        "1" | "on" | "yes" | "true" | "enabled" => "true",
+        "0" | "off" | "no" | "false" | "disabled" => "false",
+        _ => text,
+    }
+}
+
+/// Stdout of the finished `cmd`, or a message saying why it failed.
+fn outcome(cmd: &str, status: ExitStatus, stdout: &str, stderr: &str) -> Result<String, String> {
+    if !status.success() {
+        return Err(match stderr.lines().rev().find(|l| !l.trim().is_empty()) {
+            Some(line) => format!("`{cmd}` failed: {}", line.trim()),
+            None => format!("`{cmd}` failed with {status}"),
+        });
+    }
+    Ok(stdout.to_string())
+}
+
+impl Control for CommandBound {
+    fn measure(&mut self, ctx: &mut Context) -> u32 {
+        self.inner.measure(ctx)
+    }
+    fn draw(&mut self, ctx: &mut Context) {
+        self.inner.draw(ctx);
+        if self.running.is_none() {
+            return;
+        }
+        // a short bar sweeping along the bottom edge of the row
+        let bounds = ctx.canvas.viewport();
+        let sweep = Theme::busy_sweep().as_secs_f32();
+        let phase = self.started.elapsed().as_secs_f32() % sweep / sweep;
+        let bar = bounds.width() / 4;
+        let x = (phase * (bounds.width() + bar) as f32) as i32 - bar as i32;
+        let thickness = (bounds.height() / 16).max(2);
+        let rect = Rect::new(x, (bounds.height() - thickness) as i32, bar, thickness);
+        ctx.canvas.fill_rect(rect).expect("Failed to draw busy bar");
+    }
+    fn process_action(&mut self, action: &ActionKey) {
+        // the value read back would overwrite whatever is changed meanwhile
+        if !matches!(self.running, Some((Job::Get, _))) {
+            self.inner.process_action(action);
+        }
+    }
+    fn grabs_input(&self) -> bool {
+        self.inner.grabs_input()
+    }
+    fn set_editing(&mut self, editing: bool) {
+        self.inner.set_editing(editing);
+    }
+    fn hints(&self, back: &'static str) -> Vec<(&'static str, &'static str)> {
+        self.inner.hints(back)
+    }
+    fn raw_event(&mut self, event: &Event) -> bool {
+        self.inner.raw_event(event)
+    }
+    fn editing_done(&mut self) -> bool {
+        self.inner.editing_done()
+    }
+    fn focusable(&self) -> bool {
+        self.inner.focusable()
+    }
+    fn poll(&mut self) {
+        self.inner.poll();
+        if let Some((_, running)) = &mut self.running {
+            let timed_out = self.started.elapsed() > self.timeout;
+            if !running.done() && !timed_out {
+                return;
+            }
+            let (job, mut running) = self.running.take().expect("checked above");
+            let result = if running.done() {
+                running.output()
+            } else {
+                running.kill();
+                Err(format!("`{}` took longer than {} s and was stopped", running.cmd, self.timeout.as_secs()))
+            };
+            self.finish(job, result);
+        }
+        // changes made while a command ran are applied once it is done, so only the latest value gets set
+        let current = self.current();
+        if let Some(value) = current.filter(|v| Some(v) != self.applied.as_ref()) {
+            let cmd = self.set.replace("{}", &value);
+            self.run(Job::Set(value), cmd);
+        }
+    }
+    fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+    fn state(&mut self) -> Option<&mut WidgetState> {
+        self.inner.state()
+    }
+    fn state_changed(&mut self) {
+        self.inner.state_changed();
+    }
+    fn accepts(&self, state: &WidgetState) -> bool {
+        self.inner.accepts(state)
+    }
+}
+
+impl Drop for CommandBound {
+    fn drop(&mut self) {
+        if let Some((_, running)) = &mut self.running {
+            running.kill();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::Toggle;
+
+    fn toggle(set: &str, get: Option<&str>) -> CommandBound {
+        CommandBound::new(Box::new(Toggle::new(None, false)), set.to_string(), get.map(str::to_string))
+    }
+
+    /// Polls until no command is running anymore, returning the error it left.
+    fn settle(bound: &mut CommandBound) -> Option<String> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while bound.running.is_some() {
+            assert!(Instant::now() < deadline, "command should have ended");
+            thread::sleep(Duration::from_millis(5));
+            bound.poll();
+        }
+        bound.take_error()
+    }
+
+    fn on(bound: &mut CommandBound) -> bool {
+        matches!(bound.state(), Some(WidgetState::Toggle(true, _)))
+    }
+
+    #[test]
+    fn normalizes_toggle_spellings() {
+        let toggle = WidgetState::Toggle(false, 0);
+        assert_eq!(normalize(&toggle, "On"), "true");
+        assert_eq!(normalize(&toggle, "enabled"), "true");
+        assert_eq!(normalize(&toggle, "0"), "false");
+        assert_eq!(normalize(&toggle, "NO"), "false");
+        assert_eq!(normalize(&toggle, "maybe"), "maybe");
+        assert_eq!(normalize(&WidgetState::Slider(0, 0), "1"), "1");
+    }
+
+    #[test]
+    fn get_sets_the_initial_state() {
+        let mut bound = toggle("true", Some("echo enabled"));
+        assert_eq!(settle(&mut bound), None);
+        assert!(on(&mut bound));
+        assert_eq!(bound.applied.as_deref(), Some("true"));
+    }
+
+    #[test]
+    fn unexpected_output_is_reported() {
+        let mut bound = toggle("true", Some("echo sometimes"));
+        assert_eq!(settle(&mut bound).as_deref(), Some("Unexpected output \"sometimes\""));
+        assert!(!on(&mut bound));
+    }
+
+    #[test]
+    fn failed_set_is_undone() {
+        let mut bound = toggle("echo \"no permission for {}\" >&2; exit 1", None);
+        bound.process_action(&ActionKey::Press);
+        assert!(on(&mut bound));
+        bound.poll();
+        let error = settle(&mut bound);
+        assert_eq!(error.as_deref(), Some("`echo \"no permission for true\" >&2; exit 1` failed: no permission for true"));
+        assert!(!on(&mut bound));
+    }
+
+    #[test]
+    fn hung_commands_are_killed() {
+        let mut bound = toggle("true", Some("sleep 30"));
+        bound.timeout = Duration::from_millis(100);
+        let started = Instant::now();
+        let error = settle(&mut bound).expect("timeout should be reported");
+        assert!(error.contains("took longer"), "{error}");
+        assert!(started.elapsed() < Duration::from_secs(5));
+        // usable again
+        bound.process_action(&ActionKey::Press);
+        assert!(on(&mut bound));
+    }
+
+    #[test]
+    fn exit_status_without_stderr() {
+        let mut bound = toggle("true", Some("exit 3"));
+        let error = settle(&mut bound).expect("failure should be reported");
+        assert!(error.starts_with("`exit 3` failed with"), "{error}");
+    }
+}
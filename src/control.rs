@@ -0,0 +1,320 @@
+use crate::{ActionKey, WidgetState, closerize, destroy_texture, draw_circle, fill_circle};
+use sdl2::{
+    pixels::Color,
+    render::{self, TextureCreator},
+    video::{self, WindowContext},
+    rect::Rect,
+    ttf::Font,
+    event::Event,
+};
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+pub(crate) type WidgetCallback = Box<dyn Fn(&mut WidgetState, &Cell<Instant>)>;
+
+/// Resources passed to a [`Control`] when measuring and drawing its row.
+pub struct Context<'a> {
+    /// During [`Control::draw`] the viewport is set to the row, so (0, 0) is its top left
+    /// corner, and the draw color to [`Theme::fg_widgets`](crate::Theme::fg_widgets).
+    pub canvas: &'a mut render::Canvas<video::Window>,
+    pub font: &'a Font<'static, 'static>,
+    pub texture_creator: &'a TextureCreator<WindowContext>,
+    /// Width available to the row.
+    pub width: u32,
+    /// Area of the row already taken by the label and icon of the widget.
+    pub label: Rect,
+}
+
+impl Context<'_> {
+    /// Size `text` takes when drawn with [`Context::draw_text`].
+    pub fn text_size(&self, text: &str) -> (u32, u32) {
+        self.font.size_of(text).unwrap_or((0, self.font.height() as u32))
+    }
+    /// Draws `text` with its top left corner at (`x`, `y`), the texture is freed right after.
+    pub fn draw_text(&mut self, text: &str, color: impl Into<Color>, x: i32, y: i32) {
+        // SDL_ttf refuses empty text
+        if text.is_empty() {
+            return;
+        }
+        let surface = self.font.render(text).blended(color).expect("Failed to render text");
+        let texture = self.texture_creator.create_texture_from_surface(&surface)
+            .expect("Failed to create texture from surface");
+        let q = texture.query();
+        self.canvas.copy(&texture, None, Rect::new(x, y, q.width, q.height)).expect("Failed to draw text");
+        destroy_texture(texture);
+    }
+}
+
+/// Behaviour of a widget kind, the label and icon in front of it are drawn by sgui2.
+///
+/// Built-in widgets implement this too, custom ones are added with [`WidgetData::custom`](crate::builders::WidgetData::custom).
+pub trait Control {
+    /// Height of the row, the label is always fully visible regardless.
+    fn measure(&mut self, ctx: &mut Context) -> u32 {
+        ctx.label.height()
+    }
+    fn draw(&mut self, ctx: &mut Context);
+    /// Handles an action while the widget is selected, or while it holds focus if [`Control::grabs_input`] is true.
+    fn process_action(&mut self, _action: &ActionKey) {}
+    /// Whether Press enters an edit mode in which all directions are sent to [`Control::process_action`].
+    fn grabs_input(&self) -> bool {
+        false
+    }
+    /// Called when the edit mode entered through [`Control::grabs_input`] starts or ends.
+    fn set_editing(&mut self, _editing: bool) {}
+    /// Glyphs and actions shown in the hint bar during the edit mode, `back` being the label of the Back button.
+    fn hints(&self, back: &'static str) -> Vec<(&'static str, &'static str)> {
+        vec![("Arrows", "Adjust"), (back, "Done")]
+    }
+    /// Sees events from SDL before they are turned into actions while the widget is being edited,
+    /// returning true keeps the event from being handled further.
+    fn raw_event(&mut self, _event: &Event) -> bool {
+        false
+    }
+    /// Checked every frame while editing, returning true leaves the edit mode as if Back was pressed.
+    fn editing_done(&mut self) -> bool {
+        false
+    }
+    /// Whether the selection can land on this widget at all.
+    fn focusable(&self) -> bool {
+        true
+    }
+    /// Called every frame, even while the tab of the widget is not shown.
+    fn poll(&mut self) {}
+    /// Checked after [`Control::poll`], a returned message is shown as an error toast.
+    fn take_error(&mut self) -> Option<String> {
+        None
+    }
+    /// Value of built-in widgets, as passed to their callbacks.
+    fn state(&mut self) -> Option<&mut WidgetState> {
+        None
+    }
+    /// Called after the value behind [`Control::state`] was changed from outside, e.g. through the control socket.
+    fn state_changed(&mut self) {}
+    /// Whether a value from outside fits the widget, checked before it replaces the one behind [`Control::state`].
+    fn accepts(&self, _state: &WidgetState) -> bool {
+        true
+    }
+}
+
+pub(crate) struct Callback {
+    cb: Option<WidgetCallback>,
+    next: Cell<Instant>,
+}
+
+impl Callback {
+    pub(crate) fn new(cb: Option<WidgetCallback>) -> Self {
+        Self {
+            cb,
+            // make sure that function used by users is kickstarted
+            next: Cell::new(Instant::now()),
+        }
+    }
+    pub(crate) fn call(&self, state: &mut WidgetState) {
+        if let Some(cb) = &self.cb {
+            cb(state, &self.next);
+        }
+    }
+    /// Runs the callback again once the time it asked for has passed.
+    pub(crate) fn tick(&self, state: &mut WidgetState) {
+        if Instant::now() > self.next.get() {
+            self.next.set(Instant::now() + Duration::from_secs(0xFFFFFFFFF));
+            self.call(state);
+        }
+    }
+}
+
+pub(crate) fn box_metrics(ctx: &Context) -> (u32, u32) {
+    let bounds = ctx.canvas.viewport();
+    let margin = bounds.height()/4;
+    let box_size = bounds.height() - margin*2;
+    (margin, box_size)
+}
+
+pub(crate) struct Button {
+    state: WidgetState,
+    callback: Callback,
+}
+
+impl Button {
+    pub(crate) fn new(cb: Option<WidgetCallback>) -> Self {
+        Self { state: WidgetState::Button, callback: Callback::new(cb) }
+    }
+}
+
+impl Control for Button {
+    fn draw(&mut self, _ctx: &mut Context) {}
+    fn process_action(&mut self, action: &ActionKey) {
+        if *action == ActionKey::Press {
+            self.callback.call(&mut self.state);
+        }
+    }
+    fn state(&mut self) -> Option<&mut WidgetState> {
+        Some(&mut self.state)
+    }
+}
+
+pub(crate) struct Toggle {
+    state: WidgetState,
+    callback: Callback,
+}
+
+impl Toggle {
+    pub(crate) fn new(cb: Option<WidgetCallback>, state: bool) -> Self {
+        Self {
+            state: WidgetState::Toggle(state, if state { 255 } else { 0 }),
+            callback: Callback::new(cb),
+        }
+    }
+}
+
+impl Control for Toggle {
+    fn draw(&mut self, ctx: &mut Context) {
+        let bounds = ctx.canvas.viewport();
+        let (margin, box_size) = box_metrics(ctx);
+        if let WidgetState::Toggle(state, ref mut opacity) = self.state {
+            let textbox_rect = Rect::new((bounds.width() - (margin*2 + box_size)) as i32, margin as i32, box_size, box_size);
+            if state {
+                *opacity = closerize(*opacity as i32, 255) as u8;
+            } else {
+                *opacity = closerize(*opacity as i32, 0) as u8;
+            }
+            let old = ctx.canvas.draw_color();
+            ctx.canvas.set_draw_color((old.r, old.g, old.b, *opacity));
+            ctx.canvas.fill_rect(textbox_rect).expect("Failed to draw toggle widget");
+            ctx.canvas.set_draw_color((old.r, old.g, old.b));
+            ctx.canvas.draw_rect(textbox_rect).expect("Failed to draw toggle widget");
+        }
+        self.callback.tick(&mut self.state);
+    }
+    fn process_action(&mut self, action: &ActionKey) {
+        if *action == ActionKey::Press {
+            if let WidgetState::Toggle(ref mut state, _) = self.state {
+                *state = !*state;
+            }
+            self.callback.call(&mut self.state);
+        }
+    }
+    fn state(&mut self) -> Option<&mut WidgetState> {
+        Some(&mut self.state)
+    }
+    fn state_changed(&mut self) {
+        self.callback.call(&mut self.state);
+    }
+}
+
+pub(crate) struct Slider {
+    state: WidgetState,
+    callback: Callback,
+    range: (u8, u8),
+}
+
+impl Slider {
+    pub(crate) fn new(cb: Option<WidgetCallback>, state: u8) -> Self {
+        Self {
+            state: WidgetState::Slider(state, state),
+            callback: Callback::new(cb),
+            range: (u8::MIN, u8::MAX),
+        }
+    }
+    /// Keeps the value between `min` and `max`, which are swapped if reversed.
+    pub(crate) fn with_range(mut self, min: u8, max: u8) -> Self {
+        let (min, max) = (min.min(max), min.max(max));
+        self.range = (min, max);
+        if let WidgetState::Slider(ref mut value, ref mut display) = self.state {
+            *value = (*value).clamp(min, max);
+            *display = *value;
+        }
+        self
+    }
+}
+
+impl Control for Slider {
+    fn draw(&mut self, ctx: &mut Context) {
+        let bounds = ctx.canvas.viewport();
+        let (margin, box_size) = box_metrics(ctx);
+        if let WidgetState::Slider(state, ref mut display_state) = self.state {
+            if *display_state != state {
+                *display_state = closerize(*display_state as i32, state as i32) as u8;
+            }
+            // try not overlapping text
+            let label_width = ctx.label.width();
+            let whole_width = if label_width > bounds.width()/2 {
+                bounds.width() - label_width - margin*4
+            } else {
+                bounds.width()/2 - margin*4
+            };
+            let x_pos = bounds.width() - whole_width - margin*2;
+            let state_width = *display_state as f32 / u8::MAX as f32 * whole_width as f32;
+
+            let rect = Rect::new(x_pos as i32, margin as i32, whole_width, box_size);
+            let state_rect = Rect::new(x_pos as i32, margin as i32, state_width as u32, box_size);
+
+            ctx.canvas.draw_rect(rect).expect("Failed to draw slider widget");
+            ctx.canvas.fill_rect(state_rect).expect("Failed to draw slider widget");
+        }
+        self.callback.tick(&mut self.state);
+    }
+    fn process_action(&mut self, action: &ActionKey) {
+        let WidgetState::Slider(ref mut state, ..) = self.state else { return };
+        match action {
+            ActionKey::Left => *state = state.saturating_sub(12),
+            ActionKey::Right => *state = state.saturating_add(12),
+            _ => return,
+        }
+        *state = (*state).clamp(self.range.0, self.range.1);
+        self.callback.call(&mut self.state);
+    }
+    fn grabs_input(&self) -> bool {
+        true
+    }
+    fn hints(&self, back: &'static str) -> Vec<(&'static str, &'static str)> {
+        vec![("\u{2190}\u{2192}", "Adjust"), (back, "Done")]
+    }
+    fn state(&mut self) -> Option<&mut WidgetState> {
+        Some(&mut self.state)
+    }
+    fn state_changed(&mut self) {
+        self.callback.call(&mut self.state);
+    }
+    fn accepts(&self, state: &WidgetState) -> bool {
+        matches!(state, WidgetState::Slider(value, _) if (self.range.0..=self.range.1).contains(value))
+    }
+}
+
+/// One option of a radio group, deselecting the others and the group callback are handled by Tab.
+pub(crate) struct Radio {
+    state: WidgetState,
+}
+
+impl Radio {
+    pub(crate) fn new(state: bool) -> Self {
+        Self { state: WidgetState::Radio(state, if state { 255 } else { 0 }) }
+    }
+}
+
+impl Control for Radio {
+    fn draw(&mut self, ctx: &mut Context) {
+        let bounds = ctx.canvas.viewport();
+        let (margin, box_size) = box_metrics(ctx);
+        if let WidgetState::Radio(state, ref mut fill) = self.state {
+            if state {
+                *fill = closerize(*fill as i32, 255) as u8;
+            } else {
+                *fill = closerize(*fill as i32, 0) as u8;
+            }
+            let r = box_size as i32 / 2;
+            let cx = (bounds.width() - (margin*2 + box_size)) as i32 + r;
+            let cy = margin as i32 + r;
+            draw_circle(ctx.canvas, cx, cy, r).expect("Failed to draw radio widget");
+            let inner = (r - 3) * *fill as i32 / 255;
+            if inner > 0 {
+                fill_circle(ctx.canvas, cx, cy, inner).expect("Failed to draw radio widget");
+            }
+        }
+    }
+    // turned on by Tab::select_radio, which knows the rest of the group
+    fn state(&mut self) -> Option<&mut WidgetState> {
+        Some(&mut self.state)
+    }
+}
@@ -0,0 +1,238 @@
+use crate::{ActionKey, WidgetState, destroy_texture, theme::Theme, control::{Callback, Context, Control, WidgetCallback}};
+use sdl2::{rect::Rect, render::Texture};
+
+/// Calendar date and time of day, as edited by date/time widgets.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct DateTime {
+    pub year: i32,
+    /// 1-12
+    pub month: u8,
+    /// 1-31, never past the end of the month
+    pub day: u8,
+    /// 0-23
+    pub hour: u8,
+    /// 0-59
+    pub minute: u8,
+}
+
+fn is_leap(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl DateTime {
+    /// Out of range values are clamped, including the day to the length of the month.
+    pub fn new(year: i32, month: u8, day: u8, hour: u8, minute: u8) -> Self {
+        let month = month.clamp(1, 12);
+        Self {
+            year,
+            month,
+            day: day.clamp(1, days_in_month(year, month)),
+            hour: hour.min(23),
+            minute: minute.min(59),
+        }
+    }
+}
+
+/// Which parts of a [`DateTime`] a widget shows and edits.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DateTimeFields {
+    Date,
+    Time,
+    Both,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Field {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Meridiem,
+}
+
+pub(crate) struct DateTimePicker {
+    state: WidgetState,
+    callback: Callback,
+    fields: Vec<Field>,
+    twelve_hour: bool,
+    editing: bool,
+    current: usize,
+    // rendered pieces of text, re-rendered when they change
+    segments: Vec<(String, Texture)>,
+}
+
+impl DateTimePicker {
+    pub(crate) fn new(cb: Option<WidgetCallback>, value: DateTime, fields: DateTimeFields, twelve_hour: bool) -> Self {
+        let mut list = Vec::new();
+        if fields != DateTimeFields::Time {
+            list.extend([Field::Year, Field::Month, Field::Day]);
+        }
+        if fields != DateTimeFields::Date {
+            list.extend([Field::Hour, Field::Minute]);
+            if twelve_hour {
+                list.push(Field::Meridiem);
+            }
+        }
+        Self {
+            state: WidgetState::DateTime(value),
+            callback: Callback::new(cb),
+            fields: list,
+            twelve_hour,
+            editing: false,
+            current: 0,
+            segments: Vec::new(),
+        }
+    }
+    fn value(&self) -> DateTime {
+        match self.state {
+            WidgetState::DateTime(value) => value,
+            // callbacks could replace the state with anything
+            _ => DateTime::new(1970, 1, 1, 0, 0),
+        }
+    }
+    /// Text of every field and the separator after it.
+    fn pieces(&self) -> Vec<(String, &'static str)> {
+        let v = self.value();
+        self.fields.iter().enumerate().map(|(i, field)| {
+            let text = match field {
+                Field::Year => format!("{:04}", v.year),
+                Field::Month => format!("{:02}", v.month),
+                Field::Day => format!("{:02}", v.day),
+                Field::Hour if self.twelve_hour => format!("{:02}", (v.hour + 11) % 12 + 1),
+                Field::Hour => format!("{:02}", v.hour),
+                Field::Minute => format!("{:02}", v.minute),
+                Field::Meridiem => if v.hour < 12 { "AM" } else { "PM" }.to_string(),
+            };
+            let sep = match (field, self.fields.get(i + 1)) {
+                (_, None) => "",
+                (Field::Year | Field::Month, _) => "-",
+                (Field::Hour, _) => ":",
+                _ => " ",
+            };
+            (text, sep)
+        }).collect()
+    }
+    fn adjust(&mut self, up: bool) {
+        let mut v = self.value();
+        let step = |x: u8, min: u8, max: u8| match (up, x) {
+            (true, x) if x >= max => min,
+            (true, x) => x + 1,
+            (false, x) if x <= min => max,
+            (false, x) => x - 1,
+        };
+        match self.fields[self.current] {
+            Field::Year => v.year += if up { 1 } else { -1 },
+            Field::Month => v.month = step(v.month, 1, 12),
+            Field::Day => v.day = step(v.day, 1, days_in_month(v.year, v.month)),
+            Field::Hour => v.hour = step(v.hour, 0, 23),
+            Field::Minute => v.minute = step(v.minute, 0, 59),
+            Field::Meridiem => v.hour = (v.hour + 12) % 24,
+        }
+        // e.g. 31st of January becoming 28th of February
+        self.state = WidgetState::DateTime(DateTime::new(v.year, v.month, v.day, v.hour, v.minute));
+        self.callback.call(&mut self.state);
+    }
+}
+
+impl Control for DateTimePicker {
+    fn draw(&mut self, ctx: &mut Context) {
+        let pieces = self.pieces();
+        let texts = pieces.iter()
+            .flat_map(|(text, sep)| [text.clone(), sep.to_string()])
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>();
+        if texts.len() != self.segments.len() || texts.iter().zip(self.segments.iter()).any(|(a, (b, _))| a != b) {
+            for (_, old) in self.segments.drain(..) {
+                destroy_texture(old);
+            }
+            for text in texts {
+                let surface = ctx.font.render(&text).blended(Theme::fg_widgets()).expect("Failed to render text");
+                let texture = ctx.texture_creator.create_texture_from_surface(&surface)
+                    .expect("Failed to create texture from surface");
+                self.segments.push((text, texture));
+            }
+        }
+
+        let bounds = ctx.canvas.viewport();
+        let margin = ctx.label.height() as i32 / 2;
+        let total: u32 = self.segments.iter().map(|(_, t)| t.query().width).sum();
+        let mut x = bounds.width() as i32 - total as i32 - margin;
+        let mut field = 0;
+        for (text, texture) in self.segments.iter() {
+            let q = texture.query();
+            let rect = Rect::new(x, 0, q.width, q.height);
+            ctx.canvas.copy(texture, None, rect).expect("Failed to draw date/time widget");
+            // separators are never fields
+            let is_field = pieces.get(field).map(|(t, _)| t == text).unwrap_or(false);
+            if is_field {
+                if self.editing && field == self.current {
+                    ctx.canvas.draw_rect(Rect::new(x - 2, 0, q.width + 4, q.height)).expect("Failed to draw date/time widget");
+                }
+                field += 1;
+            }
+            x += q.width as i32;
+        }
+        self.callback.tick(&mut self.state);
+    }
+    fn process_action(&mut self, action: &ActionKey) {
+        if !self.editing {
+            return;
+        }
+        match action {
+            ActionKey::Left => self.current = self.current.saturating_sub(1),
+            ActionKey::Right => self.current = (self.current + 1).min(self.fields.len() - 1),
+            ActionKey::Up => self.adjust(true),
+            ActionKey::Down => self.adjust(false),
+            _ => (),
+        }
+    }
+    fn grabs_input(&self) -> bool {
+        true
+    }
+    fn hints(&self, back: &'static str) -> Vec<(&'static str, &'static str)> {
+        vec![("\u{2190}\u{2192}", "Field"), ("\u{2191}\u{2193}", "Change"), (back, "Done")]
+    }
+    fn set_editing(&mut self, editing: bool) {
+        self.editing = editing;
+    }
+    fn state(&mut self) -> Option<&mut WidgetState> {
+        Some(&mut self.state)
+    }
+    fn state_changed(&mut self) {
+        self.callback.call(&mut self.state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn month_lengths() {
+        let lengths = (1..=12).map(|m| days_in_month(2023, m)).collect::<Vec<_>>();
+        assert_eq!(lengths, [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]);
+    }
+
+    #[test]
+    fn february_in_leap_years() {
+        assert_eq!(days_in_month(2024, 2), 29);
+        assert_eq!(days_in_month(1900, 2), 28);
+        assert_eq!(days_in_month(2000, 2), 29);
+    }
+
+    #[test]
+    fn new_clamps_the_day_to_the_month() {
+        assert_eq!(DateTime::new(2023, 2, 31, 0, 0).day, 28);
+        assert_eq!(DateTime::new(2023, 13, 0, 30, 70), DateTime { year: 2023, month: 12, day: 1, hour: 23, minute: 59 });
+    }
+}
@@ -0,0 +1,656 @@
+use crate::{DateTime, DateTimeFields, TabBarPosition, WidgetState, settings,
+    builders::{GuiBuilder, TabBuilder, WidgetData}};
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Instant;
+
+type NamedCallback = Rc<dyn Fn(&mut WidgetState, &Cell<Instant>)>;
+
+/// Callbacks referred to by name from a UI definition, see [`GuiBuilder::from_str`].
+#[derive(Default)]
+pub struct Callbacks {
+    widgets: HashMap<String, NamedCallback>,
+    radio_groups: HashMap<String, Rc<dyn Fn(usize)>>,
+}
+
+impl Callbacks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Callback for `callback = "name"` of any widget except radio groups.
+    pub fn add(&mut self, name: impl ToString, cb: impl Fn(&mut WidgetState, &Cell<Instant>) + 'static) -> &mut Self {
+        self.widgets.insert(name.to_string(), Rc::new(cb));
+        self
+    }
+    /// Callback for `callback = "name"` of radio groups, getting the index of the selected option.
+    pub fn add_radio(&mut self, name: impl ToString, cb: impl Fn(usize) + 'static) -> &mut Self {
+        self.radio_groups.insert(name.to_string(), Rc::new(cb));
+        self
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Format {
+    Toml,
+    Json,
+}
+
+/// Problem with a UI definition, with the position of the offending part when known.
+#[derive(Debug)]
+pub struct DefinitionError {
+    /// 1-based
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for DefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "line {line}, column {column}: {}", self.message),
+            (Some(line), None) => write!(f, "line {line}: {}", self.message),
+            _ => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for DefinitionError {}
+
+impl DefinitionError {
+    /// Points at the value reached through `steps`, found by walking `source` to it again.
+    fn at(source: &str, format: Format, steps: &[Step], message: String) -> Self {
+        // the probe fails right before the value, the position of that error is all that's needed from it
+        let start = match format {
+            Format::Toml => Probe(steps).deserialize(toml::Deserializer::new(source)).err()
+                .and_then(|e| e.span())
+                .map(|span| span.start),
+            Format::Json => Probe(steps).deserialize(&mut serde_json::Deserializer::from_str(source)).err()
+                .map(|e| {
+                    // reported at the last character read, the colon or whitespace after it
+                    let line_start = source.split_inclusive('\n').take(e.line() - 1).map(str::len).sum::<usize>();
+                    let after = (line_start + e.column()).min(source.len());
+                    source.len() - source[after..].trim_start().len()
+                }),
+        };
+        let (line, column) = start.map(|start| line_column(source, start)).unzip();
+        Self { line, column, message }
+    }
+}
+
+/// 1-based line and column of the byte at `offset`.
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map(|i| i + 1).unwrap_or(0) + 1;
+    (line, column)
+}
+
+/// Part of the way from the top of a definition to one of its values.
+#[derive(Debug, Copy, Clone)]
+enum Step {
+    Key(&'static str),
+    Index(usize),
+}
+
+/// Skips over everything in a definition except the way along its steps, failing at the value they end at.
+struct Probe<'a>(&'a [Step]);
+
+impl<'de> DeserializeSeed<'de> for Probe<'_> {
+    type Value = ();
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        if self.0.is_empty() {
+            return Err(de::Error::custom("found"));
+        }
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Probe<'_> {
+    type Value = ();
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a table or array")
+    }
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            match self.0.split_first() {
+                Some((Step::Key(wanted), rest)) if key == *wanted => map.next_value_seed(Probe(rest))?,
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                },
+            }
+        }
+        Ok(())
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let mut index = 0;
+        loop {
+            let found = match self.0.split_first() {
+                Some((Step::Index(wanted), rest)) if index == *wanted => seq.next_element_seed(Probe(rest))?.is_some(),
+                _ => seq.next_element::<IgnoredAny>()?.is_some(),
+            };
+            if !found {
+                return Ok(());
+            }
+            index += 1;
+        }
+    }
+}
+
+/// Problem with a widget found once the definition was read, about the value of `key`.
+struct Problem {
+    key: &'static str,
+    message: String,
+}
+
+impl Problem {
+    fn at(key: &'static str, message: String) -> Self {
+        Self { key, message }
+    }
+    /// Points at the type, every widget has one.
+    fn missing(key: &'static str) -> Self {
+        Self { key: "type", message: format!("Missing field \"{key}\"") }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Document {
+    name: Option<String>,
+    #[serde(default)]
+    tab_bar: TabBarDef,
+    #[serde(default)]
+    hint_bar: bool,
+    persist_to: Option<PathBuf>,
+    #[serde(default, rename = "tab")]
+    tabs: Vec<TabDef>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum TabBarDef {
+    #[default]
+    Auto,
+    Left,
+    Top,
+    Bottom,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TabDef {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    separator: bool,
+    icon: Option<PathBuf>,
+    description: Option<String>,
+    #[serde(default)]
+    grid: bool,
+    #[serde(default, rename = "widget")]
+    widgets: Vec<WidgetDef>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WidgetDef {
+    #[serde(rename = "type")]
+    kind: Kind,
+    label: Option<String>,
+    id: Option<String>,
+    description: Option<String>,
+    callback: Option<String>,
+    icon: Option<PathBuf>,
+    disabled: Option<bool>,
+    hidden: Option<bool>,
+    value: Option<ValueDef>,
+    /// Inclusive bounds the value of a slider is kept within.
+    range: Option<(u8, u8)>,
+    fields: Option<FieldsDef>,
+    twelve_hour: Option<bool>,
+    text: Option<String>,
+    path: Option<PathBuf>,
+    size: Option<(u32, u32)>,
+    options: Option<Vec<String>>,
+    selected: Option<usize>,
+}
+
+impl WidgetDef {
+    fn keys(&self) -> impl Iterator<Item = &'static str> {
+        [
+            ("label", self.label.is_some()),
+            ("id", self.id.is_some()),
+            ("description", self.description.is_some()),
+            ("callback", self.callback.is_some()),
+            ("icon", self.icon.is_some()),
+            ("disabled", self.disabled.is_some()),
+            ("hidden", self.hidden.is_some()),
+            ("value", self.value.is_some()),
+            ("range", self.range.is_some()),
+            ("fields", self.fields.is_some()),
+            ("twelve_hour", self.twelve_hour.is_some()),
+            ("text", self.text.is_some()),
+            ("path", self.path.is_some()),
+            ("size", self.size.is_some()),
+            ("options", self.options.is_some()),
+            ("selected", self.selected.is_some()),
+        ].into_iter().filter(|(_, given)| *given).map(|(key, _)| key)
+    }
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Kind {
+    Button,
+    Toggle,
+    Slider,
+    Color,
+    DateTime,
+    KeyCapture,
+    Paragraph,
+    Image,
+    Radio,
+}
+
+impl Kind {
+    /// Whether widgets of this type make use of `key`.
+    fn takes(self, key: &str) -> bool {
+        let own: &[&str] = match self {
+            Kind::Button => &[],
+            Kind::Toggle | Kind::Color | Kind::KeyCapture => &["value"],
+            Kind::Slider => &["value", "range"],
+            Kind::DateTime => &["value", "fields", "twelve_hour"],
+            // neither labelled nor calling back
+            Kind::Paragraph => return ["id", "description", "disabled", "hidden", "text"].contains(&key),
+            Kind::Image => return ["id", "description", "disabled", "hidden", "path", "size"].contains(&key),
+            // options are plain names, the group only has a callback and the ID its selection is saved under
+            Kind::Radio => return ["id", "callback", "options", "selected"].contains(&key),
+        };
+        ["label", "id", "description", "callback", "icon", "disabled", "hidden"].contains(&key) || own.contains(&key)
+    }
+}
+
+/// `value` of any widget type, checked against the type once that is known.
+#[derive(Deserialize)]
+#[serde(untagged, expecting = "a boolean, number, text or list of numbers")]
+enum ValueDef {
+    Bool(bool),
+    Number(i64),
+    List(Vec<i64>),
+    /// Same form as in settings files, e.g. `YYYY-MM-DD HH:MM` or `key:Space`.
+    Text(String),
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum FieldsDef {
+    Date,
+    Time,
+    #[default]
+    Both,
+}
+
+fn parse(source: &str, format: Format) -> Result<Document, DefinitionError> {
+    match format {
+        Format::Toml => toml::from_str(source).map_err(|e| {
+            let (line, column) = e.span().map(|span| line_column(source, span.start)).unzip();
+            DefinitionError { line, column, message: e.message().to_string() }
+        }),
+        Format::Json => serde_json::from_str(source).map_err(|e| DefinitionError {
+            line: Some(e.line()),
+            column: Some(e.column()),
+            // serde_json appends the position on its own
+            message: e.to_string().split(" at line ").next().unwrap_or_default().to_string(),
+        }),
+    }
+}
+
+impl GuiBuilder {
+    /// Reads a TOML or JSON UI definition, picked by the file extension.
+    pub fn from_file(path: impl AsRef<Path>, callbacks: &Callbacks) -> Result<Self, DefinitionError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|e| DefinitionError {
+            line: None,
+            column: None,
+            message: format!("Failed to read {}: {e}", path.display()),
+        })?;
+        let format = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Format::Json,
+            _ => Format::Toml,
+        };
+        Self::from_str(&source, format, callbacks)
+    }
+    /// Builds tabs and widgets described by `source`, callbacks are looked up by name in `callbacks`.
+    ///
+    /// ```toml
+    /// name = "Settings"
+    ///
+    /// [[tab]]
+    /// name = "Network"
+    ///
+    /// [[tab.widget]]
+    /// type = "toggle"
+    /// label = "Wi-Fi"
+    /// id = "wifi"
+    /// value = true
+    /// callback = "set_wifi"
+    /// ```
+    pub fn from_str(source: &str, format: Format, callbacks: &Callbacks) -> Result<Self, DefinitionError> {
+        let document = parse(source, format)?;
+        let mut gui = GuiBuilder::new(document.name.as_deref().unwrap_or("sgui2"));
+        gui.tab_bar_position(match document.tab_bar {
+            TabBarDef::Auto => TabBarPosition::Auto,
+            TabBarDef::Left => TabBarPosition::Left,
+            TabBarDef::Top => TabBarPosition::Top,
+            TabBarDef::Bottom => TabBarPosition::Bottom,
+        });
+        gui.hint_bar(document.hint_bar);
+        if let Some(path) = document.persist_to {
+            gui.persist_to(path);
+        }
+
+        for (t, tab_def) in document.tabs.into_iter().enumerate() {
+            if tab_def.separator {
+                gui.tab_separator();
+                continue;
+            }
+            let mut tab = TabBuilder::new(&tab_def.name);
+            if let Some(icon) = tab_def.icon {
+                tab.icon(icon);
+            }
+            if let Some(description) = tab_def.description {
+                tab.description(description);
+            }
+            if tab_def.grid {
+                tab.grid();
+            }
+            for (w, widget_def) in tab_def.widgets.into_iter().enumerate() {
+                let data = widget(&mut tab, widget_def, callbacks).map_err(|problem| {
+                    let steps = [Step::Key("tab"), Step::Index(t), Step::Key("widget"), Step::Index(w), Step::Key(problem.key)];
+                    DefinitionError::at(source, format, &steps, problem.message)
+                })?;
+                if let Some(data) = data {
+                    tab.widget(data);
+                }
+            }
+            gui.tab(&mut tab);
+        }
+        Ok(gui)
+    }
+}
+
+/// Turns a widget definition into a widget, radio groups are added to `tab` directly.
+fn widget(tab: &mut TabBuilder, def: WidgetDef, callbacks: &Callbacks) -> Result<Option<WidgetData>, Problem> {
+    if let Some(key) = def.keys().find(|key| !def.kind.takes(key)) {
+        return Err(Problem::at(key, format!("Key \"{key}\" doesn't apply to this type of widget")));
+    }
+    let label = def.label.unwrap_or_default();
+    if def.kind == Kind::Radio {
+        let options = def.options.ok_or_else(|| Problem::missing("options"))?;
+        if options.is_empty() {
+            return Err(Problem::at("options", "A radio group needs at least one option".to_string()));
+        }
+        let selected = def.selected.unwrap_or(0);
+        if selected >= options.len() {
+            return Err(Problem::at("selected", format!("Selected option {selected} is past the last of {} options", options.len())));
+        }
+        let cb = match &def.callback {
+            Some(name) => callbacks.radio_groups.get(name).cloned()
+                .ok_or_else(|| Problem::at("callback", format!("Unknown radio group callback \"{name}\"")))?,
+            None => Rc::new(|_| {}),
+        };
+        match def.id {
+            Some(id) => tab.radio_group_with_id(id, options, selected, move |i| cb(i)),
+            None => tab.radio_group(options, selected, move |i| cb(i)),
+        };
+        return Ok(None);
+    }
+
+    let cb: NamedCallback = match &def.callback {
+        Some(name) => callbacks.widgets.get(name).cloned()
+            .ok_or_else(|| Problem::at("callback", format!("Unknown callback \"{name}\" of widget \"{label}\"")))?,
+        None => Rc::new(|_, _| {}),
+    };
+    let call = move |state: &mut WidgetState, next: &Cell<Instant>| cb(state, next);
+    // text values take the form of the settings file
+    let decoded = |mut state: WidgetState| match &def.value {
+        None => Ok(state),
+        Some(ValueDef::Text(text)) if settings::decode(&mut state, text) => Ok(state),
+        Some(ValueDef::Text(text)) => Err(Problem::at("value", format!("Invalid value \"{text}\" of widget \"{label}\""))),
+        Some(_) => Err(Problem::at("value", format!("Value of widget \"{label}\" should be text"))),
+    };
+    let byte = |n: i64| u8::try_from(n).ok();
+
+    let mut data = match def.kind {
+        Kind::Button => WidgetData::btn(&label, call),
+        Kind::Toggle => {
+            let value = match def.value {
+                None => false,
+                Some(ValueDef::Bool(value)) => value,
+                Some(_) => return Err(Problem::at("value", "Value of a toggle should be true or false".to_string())),
+            };
+            WidgetData::toggle(&label, call, value)
+        },
+        Kind::Slider => {
+            let value = match def.value {
+                None => 0,
+                Some(ValueDef::Number(n)) if byte(n).is_some() => n as u8,
+                Some(_) => return Err(Problem::at("value", "Value of a slider should be a number from 0 to 255".to_string())),
+            };
+            match def.range {
+                None => WidgetData::slider(&label, call, value),
+                Some((min, max)) if min > max => return Err(Problem::at("range", format!("Range of a slider should be [min, max], not [{min}, {max}]"))),
+                Some((min, max)) => WidgetData::slider_with_range(&label, call, value, min, max),
+            }
+        },
+        Kind::Color => {
+            let value = match &def.value {
+                None => (255, 255, 255),
+                Some(ValueDef::List(list)) => match list.iter().map(|n| byte(*n)).collect::<Vec<_>>()[..] {
+                    [Some(r), Some(g), Some(b)] => (r, g, b),
+                    _ => return Err(Problem::at("value", "Value of a color should be [red, green, blue], each from 0 to 255".to_string())),
+                },
+                Some(_) => return Err(Problem::at("value", "Value of a color should be [red, green, blue], each from 0 to 255".to_string())),
+            };
+            WidgetData::color(&label, call, value)
+        },
+        Kind::DateTime => {
+            if def.value.is_none() {
+                return Err(Problem::missing("value"));
+            }
+            let WidgetState::DateTime(value) = decoded(WidgetState::DateTime(DateTime::new(1970, 1, 1, 0, 0)))? else { unreachable!() };
+            let fields = match def.fields.unwrap_or_default() {
+                FieldsDef::Date => DateTimeFields::Date,
+                FieldsDef::Time => DateTimeFields::Time,
+                FieldsDef::Both => DateTimeFields::Both,
+            };
+            WidgetData::date_time(&label, call, value, fields, def.twelve_hour.unwrap_or(false))
+        },
+        Kind::KeyCapture => {
+            let WidgetState::Binding(binding) = decoded(WidgetState::Binding(None))? else { unreachable!() };
+            WidgetData::key_capture(&label, call, binding)
+        },
+        Kind::Paragraph => WidgetData::paragraph(def.text.ok_or_else(|| Problem::missing("text"))?),
+        Kind::Image => WidgetData::image(def.path.ok_or_else(|| Problem::missing("path"))?, def.size),
+        Kind::Radio => unreachable!("handled above"),
+    };
+
+    if let Some(id) = def.id {
+        data = data.with_id(id);
+    }
+    if let Some(description) = def.description {
+        data = data.with_description(description);
+    }
+    if let Some(icon) = def.icon {
+        data = data.with_icon(icon);
+    }
+    if def.disabled == Some(true) {
+        data = data.disabled();
+    }
+    if def.hidden == Some(true) {
+        data = data.hidden();
+    }
+    Ok(Some(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn callbacks() -> Callbacks {
+        let mut callbacks = Callbacks::new();
+        callbacks.add("print", |_, _| {}).add_radio("governor", |_| {});
+        callbacks
+    }
+
+    /// Line and column of the error reading `source`, or panics if it was fine.
+    fn error_at(source: &str, format: Format) -> (usize, usize, String) {
+        match GuiBuilder::from_str(source, format, &callbacks()) {
+            Ok(_) => panic!("{source} should have been rejected"),
+            Err(e) => (e.line.expect("error should have a line"), e.column.expect("error should have a column"), e.message),
+        }
+    }
+
+    const TOML: &str = r#"
+name = "Test"
+hint_bar = true
+
+[[tab]]
+name = "One"
+
+[[tab.widget]]
+type = "toggle"
+label = "Wi-Fi"
+id = "wifi"
+value = true
+callback = "print"
+
+[[tab.widget]]
+type = "slider"
+label = "Volume"
+value = 100
+range = [20, 200]
+
+[[tab]]
+separator = true
+
+[[tab]]
+name = "Two"
+
+[[tab.widget]]
+type = "radio"
+options = ["a", "b"]
+selected = 1
+callback = "governor"
+
+[[tab.widget]]
+type = "date_time"
+label = "Clock"
+value = "2024-01-01 12:00"
+fields = "date"
+
+[[tab.widget]]
+type = "color"
+value = [1, 2, 3]
+
+[[tab.widget]]
+type = "paragraph"
+text = "Hello"
+hidden = true
+"#;
+
+    #[test]
+    fn reads_toml() {
+        assert!(GuiBuilder::from_str(TOML, Format::Toml, &callbacks()).is_ok());
+    }
+
+    #[test]
+    fn reads_json() {
+        let source = r#"{
+            "name": "Test",
+            "tab": [{
+                "name": "One",
+                "widget": [
+                    {"type": "button", "label": "Go", "callback": "print"},
+                    {"type": "radio", "options": ["a", "b"]}
+                ]
+            }]
+        }"#;
+        assert!(GuiBuilder::from_str(source, Format::Json, &callbacks()).is_ok());
+    }
+
+    #[test]
+    fn unknown_callback_points_at_it() {
+        let source = TOML.replacen("callback = \"print\"", "callback = \"pritn\"", 1);
+        assert_eq!(error_at(&source, Format::Toml), (13, 12, "Unknown callback \"pritn\" of widget \"Wi-Fi\"".to_string()));
+        let source = TOML.replacen("callback = \"governor\"", "callback = \"print\"", 1);
+        assert_eq!(error_at(&source, Format::Toml).0, 31);
+    }
+
+    #[test]
+    fn unknown_callback_in_json() {
+        let source = "{\"tab\": [{\"name\": \"One\", \"widget\": [\n  {\"type\": \"button\", \"label\": \"print\"},\n  {\"type\": \"button\", \"label\": \"b\", \"callback\": \"x\"}\n]}]}";
+        let (line, column, message) = error_at(source, Format::Json);
+        assert_eq!((line, column), (3, 48));
+        assert_eq!(message, "Unknown callback \"x\" of widget \"b\"");
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let source = TOML.replacen("label = \"Volume\"", "lable = \"Volume\"", 1);
+        let (line, column, message) = error_at(&source, Format::Toml);
+        assert_eq!((line, column), (17, 1));
+        assert!(message.contains("unknown field `lable`"), "{message}");
+    }
+
+    #[test]
+    fn keys_of_other_types_are_rejected() {
+        let source = TOML.replacen("id = \"wifi\"", "range = [1, 2]", 1);
+        assert_eq!(error_at(&source, Format::Toml), (11, 9, "Key \"range\" doesn't apply to this type of widget".to_string()));
+        let source = TOML.replacen("selected = 1", "label = \"Governor\"", 1);
+        assert_eq!(error_at(&source, Format::Toml).0, 30);
+    }
+
+    #[test]
+    fn reversed_range_is_rejected() {
+        let source = TOML.replacen("range = [20, 200]", "range = [200, 20]", 1);
+        assert_eq!(error_at(&source, Format::Toml), (19, 9, "Range of a slider should be [min, max], not [200, 20]".to_string()));
+    }
+
+    #[test]
+    fn radio_options_are_checked() {
+        let source = TOML.replacen("options = [\"a\", \"b\"]", "options = []", 1);
+        assert_eq!(error_at(&source, Format::Toml), (29, 11, "A radio group needs at least one option".to_string()));
+        let source = TOML.replacen("selected = 1", "selected = 2", 1);
+        assert_eq!(error_at(&source, Format::Toml), (30, 12, "Selected option 2 is past the last of 2 options".to_string()));
+    }
+
+    #[test]
+    fn bad_values_point_at_them() {
+        let source = TOML.replacen("2024-01-01 12:00", "yesterday", 1);
+        assert_eq!(error_at(&source, Format::Toml), (36, 9, "Invalid value \"yesterday\" of widget \"Clock\"".to_string()));
+        let source = TOML.replacen("value = true", "value = 1", 1);
+        assert_eq!(error_at(&source, Format::Toml).0, 12);
+        let source = TOML.replacen("value = 100", "value = 256", 1);
+        assert_eq!(error_at(&source, Format::Toml).0, 18);
+        let source = TOML.replacen("value = [1, 2, 3]", "value = [1, 2]", 1);
+        assert_eq!(error_at(&source, Format::Toml).0, 41);
+    }
+
+    #[test]
+    fn missing_fields_point_at_the_type() {
+        let source = TOML.replacen("text = \"Hello\"", "", 1);
+        assert_eq!(error_at(&source, Format::Toml), (44, 8, "Missing field \"text\"".to_string()));
+    }
+
+    #[test]
+    fn syntax_errors_have_positions() {
+        assert_eq!(error_at("name = \n", Format::Toml).0, 1);
+        assert_eq!(error_at("{\"name\": }", Format::Json), (1, 10, "expected value".to_string()));
+    }
+}
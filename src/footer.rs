@@ -0,0 +1,71 @@
+use crate::{closerize, destroy_texture, text, theme::Theme};
+use sdl2::{
+    render::{self, Texture, TextureCreator},
+    video::{self, WindowContext},
+    rect::Rect,
+    ttf::Font,
+};
+
+/// Description of whatever is selected, shown across the bottom of the window.
+#[derive(Default)]
+pub(crate) struct Footer {
+    shown: Option<String>,
+    width: u32,
+    lines: Vec<Texture>,
+    line_height: u32,
+    // animated towards the height of the text, or 0 without one
+    height: i32,
+    alpha: u8,
+}
+
+impl Footer {
+    pub(crate) fn height(&self) -> u32 {
+        self.height as u32
+    }
+    pub(crate) fn tick(&mut self, text: Option<&str>, font: &Font, texture_creator: &TextureCreator<WindowContext>, width: u32) {
+        let pad = Theme::padding() as u32 * font.height() as u32 / 100;
+        if text != self.shown.as_deref() || (text.is_some() && width != self.width) {
+            self.shown = text.map(str::to_string);
+            self.width = width;
+            // old lines stay around while the footer shrinks away
+            if let Some(text) = text {
+                for old in self.lines.drain(..) {
+                    destroy_texture(old);
+                }
+                self.line_height = font.recommended_line_spacing().max(font.height()) as u32;
+                self.lines = text::wrap(text, font, width.saturating_sub(pad*2)).iter()
+                    .take(Theme::footer_max_rows())
+                    .map(|line| {
+                        let line = if line.is_empty() { " " } else { line };
+                        let surface = font.render(line).blended(Theme::fg_widgets()).expect("Failed to render text");
+                        texture_creator.create_texture_from_surface(&surface).expect("Failed to create texture from surface")
+                    })
+                    .collect();
+                self.alpha = 0;
+            }
+        }
+        let target = match self.shown {
+            Some(_) => (self.lines.len() as u32 * self.line_height + pad*2) as i32,
+            None => 0,
+        };
+        self.height = closerize(self.height, target);
+        self.alpha = closerize(self.alpha as i32, 255) as u8;
+    }
+    pub(crate) fn draw(&mut self, canvas: &mut render::Canvas<video::Window>, (width, height): (u32, u32), font_height: i32) {
+        if self.height == 0 {
+            return;
+        }
+        let area = Rect::new(0, height as i32 - self.height, width, self.height as u32);
+        canvas.set_viewport(area);
+        canvas.set_draw_color(Theme::bg_tabs());
+        canvas.fill_rect(Rect::new(0, 0, width, area.height())).expect("Failed to draw footer");
+        let pad = Theme::padding() as i32 * font_height / 100;
+        for (i, line) in self.lines.iter_mut().enumerate() {
+            let q = line.query();
+            line.set_alpha_mod(self.alpha);
+            let y = pad + i as i32 * self.line_height as i32;
+            canvas.copy(line, None, Rect::new(pad, y, q.width, q.height)).expect("Failed to draw footer");
+        }
+        canvas.set_viewport(None);
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{destroy_texture, theme::Theme, control::{Context, Control}};
+use sdl2::{
+    render::Texture,
+    rect::{Point, Rect},
+};
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum GraphStyle {
+    #[default]
+    Line,
+    Bar,
+}
+
+/// Cloneable handle for pushing samples into a [`Graph`], also from other threads.
+#[derive(Debug, Clone)]
+pub struct GraphFeed {
+    tx: Sender<(usize, f32)>,
+}
+
+impl GraphFeed {
+    /// Appends a sample to the first series.
+    pub fn push(&self, value: f32) {
+        self.push_to(0, value);
+    }
+    /// Appends a sample to `series`, which is created on first use. NaN and infinite samples are dropped.
+    pub fn push_to(&self, series: usize, value: f32) {
+        // receiver only goes away together with the Gui, nothing to report to then
+        let _ = self.tx.send((series, value));
+    }
+}
+
+/// Rolling window of samples drawn as a line or bar chart, with the newest value next to the label.
+pub struct Graph {
+    rx: Receiver<(usize, f32)>,
+    samples: usize,
+    series: Vec<VecDeque<f32>>,
+    style: GraphStyle,
+    // None scales to the samples currently shown
+    range: Option<(f32, f32)>,
+    unit: String,
+    readout: Option<(String, Texture)>,
+}
+
+impl Graph {
+    /// Graph keeping the last `samples` values of each series.
+    pub fn new(samples: usize) -> (Self, GraphFeed) {
+        let (tx, rx) = mpsc::channel();
+        let graph = Self {
+            rx,
+            samples: samples.max(2),
+            series: Vec::new(),
+            style: GraphStyle::Line,
+            range: None,
+            unit: String::new(),
+            readout: None,
+        };
+        (graph, GraphFeed { tx })
+    }
+    pub fn style(mut self, style: GraphStyle) -> Self {
+        self.style = style;
+        self
+    }
+    /// Fixed Y range instead of scaling to the samples.
+    /// Reversed bounds are swapped, a range with a NaN bound is ignored.
+    pub fn range(mut self, min: f32, max: f32) -> Self {
+        self.range = match (min.is_nan() || max.is_nan(), min > max) {
+            (true, _) => None,
+            (false, true) => Some((max, min)),
+            (false, false) => Some((min, max)),
+        };
+        self
+    }
+    /// Appended to the current value, e.g. "°C".
+    pub fn unit(mut self, unit: impl ToString) -> Self {
+        self.unit = unit.to_string();
+        self
+    }
+    fn y_range(&self) -> (f32, f32) {
+        let (min, max) = self.range.unwrap_or_else(|| self.series.iter()
+            .flatten()
+            .fold((f32::MAX, f32::MIN), |(min, max), v| (min.min(*v), max.max(*v))));
+        if min > max {
+            (0.0, 1.0)
+        } else if min == max {
+            (min - 1.0, max + 1.0)
+        } else {
+            (min, max)
+        }
+    }
+    fn readout_text(&self) -> String {
+        self.series.iter()
+            .filter_map(|s| s.back())
+            .map(|v| format!("{v:.1}{}", self.unit))
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+}
+
+impl Control for Graph {
+    fn poll(&mut self) {
+        for (series, value) in self.rx.try_iter() {
+            // NaN and infinities can't be scaled to the chart
+            if !value.is_finite() {
+                continue;
+            }
+            if self.series.len() <= series {
+                self.series.resize_with(series + 1, VecDeque::new);
+            }
+            let samples = &mut self.series[series];
+            if samples.len() == self.samples {
+                samples.pop_front();
+            }
+            samples.push_back(value);
+        }
+    }
+    fn measure(&mut self, ctx: &mut Context) -> u32 {
+        ctx.label.height() + ctx.font.height() as u32 * Theme::graph_rows()
+    }
+    fn draw(&mut self, ctx: &mut Context) {
+        let bounds = ctx.canvas.viewport();
+        let label_height = ctx.label.height();
+        let margin = label_height as i32 / 2;
+
+        let text = self.readout_text();
+        if !text.is_empty() && self.readout.as_ref().map(|(t, _)| *t != text).unwrap_or(true) {
+            let surface = ctx.font.render(&text).blended(Theme::fg_widgets()).expect("Failed to render text");
+            let texture = ctx.texture_creator.create_texture_from_surface(&surface)
+                .expect("Failed to create texture from surface");
+            if let Some((_, old)) = self.readout.replace((text, texture)) {
+                destroy_texture(old);
+            }
+        }
+        if let Some((_, texture)) = &self.readout {
+            let q = texture.query();
+            let x = bounds.width() as i32 - q.width as i32 - margin;
+            ctx.canvas.copy(texture, None, Rect::new(x, 0, q.width, q.height)).expect("Failed to draw graph widget");
+        }
+
+        let area = Rect::new(0, label_height as i32, bounds.width() - label_height, bounds.height() - label_height);
+        ctx.canvas.draw_rect(area).expect("Failed to draw graph widget");
+        let (min, max) = self.y_range();
+        let inner_h = area.height() as f32 - 2.0;
+        let to_y = |v: f32| {
+            let v = v.clamp(min, max);
+            area.bottom() - 1 - ((v - min) / (max - min) * inner_h) as i32
+        };
+        let slot = (area.width() - 2) as f32 / self.samples as f32;
+
+        let old = ctx.canvas.draw_color();
+        let series_count = self.series.len().max(1);
+        for (n, samples) in self.series.iter().enumerate() {
+            ctx.canvas.set_draw_color(Theme::graph_series(n));
+            // newest sample sits at the right edge
+            let first = self.samples - samples.len();
+            match self.style {
+                GraphStyle::Line => {
+                    let points = samples.iter()
+                        .enumerate()
+                        .map(|(i, v)| Point::new(area.x() + 1 + ((first + i) as f32 * slot + slot/2.0) as i32, to_y(*v)))
+                        .collect::<Vec<_>>();
+                    ctx.canvas.draw_lines(&points[..]).expect("Failed to draw graph widget");
+                },
+                GraphStyle::Bar => {
+                    let bar_w = (slot / series_count as f32).max(1.0);
+                    for (i, v) in samples.iter().enumerate() {
+                        let x = area.x() + 1 + ((first + i) as f32 * slot + n as f32 * bar_w) as i32;
+                        let y = to_y(*v);
+                        let h = (area.bottom() - 1 - y).max(1) as u32;
+                        ctx.canvas.fill_rect(Rect::new(x, y, bar_w as u32, h)).expect("Failed to draw graph widget");
+                    }
+                },
+            }
+        }
+        ctx.canvas.set_draw_color(old);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reversed_range_is_swapped() {
+        let (graph, _) = Graph::new(10);
+        assert_eq!(graph.range(100.0, 0.0).y_range(), (0.0, 100.0));
+    }
+
+    #[test]
+    fn bad_ranges_fall_back() {
+        let (graph, _) = Graph::new(10);
+        let graph = graph.range(f32::NAN, 5.0);
+        assert_eq!(graph.y_range(), (0.0, 1.0));
+        // a flat range is widened like flat samples
+        assert_eq!(graph.range(5.0, 5.0).y_range(), (4.0, 6.0));
+    }
+
+    #[test]
+    fn drops_samples_that_cannot_be_drawn() {
+        let (mut graph, feed) = Graph::new(10);
+        for value in [1.0, f32::NAN, 3.0, f32::INFINITY, f32::NEG_INFINITY] {
+            feed.push(value);
+        }
+        graph.poll();
+        assert_eq!(graph.series[0], [1.0, 3.0]);
+        assert_eq!(graph.y_range(), (1.0, 3.0));
+    }
+
+    #[test]
+    fn keeps_the_last_samples() {
+        let (mut graph, feed) = Graph::new(3);
+        for value in 0..5 {
+            feed.push_to(1, value as f32);
+        }
+        graph.poll();
+        assert!(graph.series[0].is_empty());
+        assert_eq!(graph.series[1], [2.0, 3.0, 4.0]);
+        assert_eq!(graph.readout_text(), "4.0");
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{destroy_texture, theme::Theme};
+use sdl2::{
+    render::{self, Texture, TextureCreator},
+    video::{self, WindowContext},
+    rect::Rect,
+    ttf::Font,
+};
+
+/// Button labels of the device last used for input.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub(crate) enum Glyphs {
+    #[default]
+    Keyboard,
+    Xbox,
+    PlayStation,
+    /// Confirm and back are swapped compared to Xbox labels.
+    Nintendo,
+}
+
+impl Glyphs {
+    /// Guesses the button labels from the name SDL gives the controller.
+    pub(crate) fn from_controller_name(name: &str) -> Self {
+        let name = name.to_lowercase();
+        if ["playstation", "ps3", "ps4", "ps5", "dualshock", "dualsense"].iter().any(|n| name.contains(n)) {
+            Glyphs::PlayStation
+        } else if ["nintendo", "switch", "joy-con"].iter().any(|n| name.contains(n)) {
+            Glyphs::Nintendo
+        } else {
+            Glyphs::Xbox
+        }
+    }
+    pub(crate) fn press(self) -> &'static str {
+        match self {
+            Glyphs::Keyboard => "Enter",
+            Glyphs::Xbox => "A",
+            Glyphs::PlayStation => "Cross",
+            Glyphs::Nintendo => "B",
+        }
+    }
+    pub(crate) fn back(self) -> &'static str {
+        match self {
+            Glyphs::Keyboard => "Esc",
+            Glyphs::Xbox => "B",
+            Glyphs::PlayStation => "Circle",
+            Glyphs::Nintendo => "A",
+        }
+    }
+    pub(crate) fn switch_tab(self) -> &'static str {
+        match self {
+            Glyphs::Keyboard => "Tab",
+            Glyphs::Xbox => "LB/RB",
+            Glyphs::PlayStation => "L1/R1",
+            Glyphs::Nintendo => "L/R",
+        }
+    }
+}
+
+/// Row of the actions available right now, along the bottom of the window.
+#[derive(Default)]
+pub(crate) struct HintBar {
+    text: Option<(String, Texture)>,
+}
+
+impl HintBar {
+    pub(crate) fn height(font_height: i32) -> u32 {
+        let pad = Theme::padding() as i32 * font_height / 100;
+        (font_height + pad) as u32
+    }
+    pub(crate) fn draw(&mut self, canvas: &mut render::Canvas<video::Window>, hints: &[(&str, &str)], font: &Font, texture_creator: &TextureCreator<WindowContext>, (width, height): (u32, u32)) {
+        let text = hints.iter()
+            .map(|(glyph, action)| format!("{glyph} {action}"))
+            .collect::<Vec<_>>()
+            .join("     ");
+        if self.text.as_ref().map(|(t, _)| *t != text).unwrap_or(true) {
+            let rendered = if text.is_empty() { " " } else { &text };
+            let surface = font.render(rendered).blended(Theme::fg_widgets()).expect("Failed to render text");
+            let texture = texture_creator.create_texture_from_surface(&surface)
+                .expect("Failed to create texture from surface");
+            if let Some((_, old)) = self.text.replace((text, texture)) {
+                destroy_texture(old);
+            }
+        }
+        let bar_height = Self::height(font.height());
+        let area = Rect::new(0, height as i32 - bar_height as i32, width, bar_height);
+        canvas.set_draw_color(Theme::bg_tabs());
+        canvas.fill_rect(area).expect("Failed to draw hint bar");
+        if let Some((_, texture)) = &self.text {
+            let q = texture.query();
+            let pad = Theme::padding() as i32 * font.height() / 100;
+            let y = area.y() + (bar_height as i32 - q.height as i32) / 2;
+            canvas.copy(texture, None, Rect::new(pad, y, q.width, q.height)).expect("Failed to draw hint bar");
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use crate::control::{Context, Control};
+use derivative::Derivative;
+use sdl2::{
+    image::{self, InitFlag, LoadTexture, Sdl2ImageContext},
+    pixels::PixelFormatEnum,
+    rect::Rect,
+    render::{Texture, TextureCreator},
+    surface::Surface,
+    video::WindowContext,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Where to get pixels for an icon or an image widget from.
+#[derive(Debug, Clone)]
+pub enum ImageSource {
+    /// PNG or JPEG file, decoded once and shared between all users of the same path.
+    File(PathBuf),
+    /// Tightly packed 8-bit RGBA pixels.
+    Rgba {
+        width: u32,
+        height: u32,
+        data: Vec<u8>,
+    },
+}
+
+impl From<&str> for ImageSource {
+    fn from(path: &str) -> Self {
+        ImageSource::File(path.into())
+    }
+}
+
+impl From<&Path> for ImageSource {
+    fn from(path: &Path) -> Self {
+        ImageSource::File(path.into())
+    }
+}
+
+impl From<PathBuf> for ImageSource {
+    fn from(path: PathBuf) -> Self {
+        ImageSource::File(path)
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub(crate) struct ImageCache {
+    #[derivative(Debug="ignore")]
+    _context: Sdl2ImageContext,
+    #[derivative(Debug="ignore")]
+    files: HashMap<PathBuf, Rc<Texture>>,
+}
+
+impl ImageCache {
+    pub(crate) fn new() -> Self {
+        Self {
+            _context: image::init(InitFlag::PNG | InitFlag::JPG).expect("Failed to initialize SDL_image"),
+            files: HashMap::new(),
+        }
+    }
+    /// Missing or broken images show up as a placeholder rather than taking the Gui down.
+    pub(crate) fn load(&mut self, source: ImageSource, texture_creator: &TextureCreator<WindowContext>) -> Rc<Texture> {
+        match source {
+            ImageSource::File(path) => {
+                self.files.entry(path)
+                    .or_insert_with_key(|path| {
+                        let texture = texture_creator.load_texture(path)
+                            .unwrap_or_else(|_| placeholder(texture_creator));
+                        Rc::new(texture)
+                    })
+                    .clone()
+            },
+            ImageSource::Rgba { width, height, data } => {
+                Rc::new(from_rgba(width, height, data, texture_creator).unwrap_or_else(|| placeholder(texture_creator)))
+            },
+        }
+    }
+}
+
+/// None if `data` doesn't hold `width` x `height` pixels.
+fn from_rgba(width: u32, height: u32, mut data: Vec<u8>, texture_creator: &TextureCreator<WindowContext>) -> Option<Texture> {
+    if data.len() as u64 != width as u64 * height as u64 * 4 {
+        return None;
+    }
+    let surface = Surface::from_data(&mut data, width, height, width * 4, PixelFormatEnum::RGBA32).ok()?;
+    texture_creator.create_texture_from_surface(&surface).ok()
+}
+
+/// Magenta and black checkerboard standing in for images that couldn't be loaded.
+fn placeholder(texture_creator: &TextureCreator<WindowContext>) -> Texture {
+    const SIZE: u32 = 8;
+    let data = (0..SIZE * SIZE)
+        .flat_map(|i| if (i % SIZE + i / SIZE) % 2 == 0 { [255, 0, 255, 255] } else { [0, 0, 0, 255] })
+        .collect();
+    from_rgba(SIZE, SIZE, data, texture_creator).expect("Failed to create placeholder image")
+}
+
+/// Size of `texture` scaled to `height` while keeping its aspect ratio.
+pub(crate) fn scaled_to_height(texture: &Texture, height: u32) -> (u32, u32) {
+    let q = texture.query();
+    if q.height == 0 {
+        return (0, 0);
+    }
+    (q.width * height / q.height, height)
+}
+
+/// Largest size of `texture` that fits into `width` x `height` while keeping its aspect ratio.
+pub(crate) fn scaled_to_fit(texture: &Texture, width: u32, height: u32) -> (u32, u32) {
+    let (w, h) = scaled_to_height(texture, height);
+    if w <= width {
+        return (w, h);
+    }
+    let q = texture.query();
+    (width, q.height * width / q.width.max(1))
+}
+
+/// Standalone image widget.
+pub(crate) struct Picture {
+    texture: Rc<Texture>,
+    size: (u32, u32),
+}
+
+impl Picture {
+    /// Natural size of the texture if `size` is `None`.
+    pub(crate) fn new(texture: Rc<Texture>, size: Option<(u32, u32)>) -> Self {
+        let q = texture.query();
+        Self {
+            size: size.unwrap_or((q.width, q.height)),
+            texture,
+        }
+    }
+}
+
+impl Control for Picture {
+    fn measure(&mut self, _ctx: &mut Context) -> u32 {
+        self.size.1
+    }
+    fn draw(&mut self, ctx: &mut Context) {
+        let (w, h) = self.size;
+        ctx.canvas.copy(&self.texture, None, Rect::new(ctx.label.right(), 0, w, h))
+            .expect("Failed to draw image widget");
+    }
+}
@@ -0,0 +1,471 @@
+use crate::{ActionKey, Focus, Gui, GuiEvent, Severity, Widget, WidgetState, settings};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+enum Incoming {
+    Connected(u64, Sender<String>),
+    Line(u64, String),
+    Disconnected(u64),
+}
+
+struct Client {
+    tx: Sender<String>,
+    subscribed: bool,
+}
+
+/// Unix domain socket taking one JSON request per line, see [`GuiBuilder::control_socket`](crate::builders::GuiBuilder::control_socket).
+pub(crate) struct ControlSocket {
+    path: PathBuf,
+    rx: Receiver<Incoming>,
+    clients: HashMap<u64, Client>,
+}
+
+impl ControlSocket {
+    /// Replaces a socket file left behind by a previous run, but not one something is still listening on
+    /// or anything that isn't a socket.
+    pub(crate) fn bind(path: PathBuf) -> io::Result<Self> {
+        if let Ok(metadata) = fs::symlink_metadata(&path) {
+            if !metadata.file_type().is_socket() {
+                return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} exists and is not a socket", path.display())));
+            }
+            if UnixStream::connect(&path).is_err() {
+                fs::remove_file(&path)?;
+            }
+        }
+        let listener = UnixListener::bind(&path)?;
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || accept(listener, tx));
+        Ok(Self { path, rx, clients: HashMap::new() })
+    }
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn accept(listener: UnixListener, tx: Sender<Incoming>) {
+    for (stream, id) in listener.incoming().flatten().zip(0..) {
+        let Ok(writer) = stream.try_clone() else { continue };
+        let (out_tx, out_rx) = mpsc::channel::<String>();
+        if tx.send(Incoming::Connected(id, out_tx)).is_err() {
+            // the Gui is gone
+            return;
+        }
+        thread::spawn(move || {
+            let mut writer = writer;
+            for line in out_rx {
+                if writeln!(writer, "{line}").is_err() {
+                    break;
+                }
+            }
+        });
+        let tx = tx.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(stream).lines() {
+                let Ok(line) = line else { break };
+                if tx.send(Incoming::Line(id, line)).is_err() {
+                    return;
+                }
+            }
+            let _ = tx.send(Incoming::Disconnected(id));
+        });
+    }
+}
+
+#[derive(Deserialize)]
+struct Envelope {
+    /// Copied into the reply, so clients can match them up.
+    id: Option<Value>,
+    #[serde(flatten)]
+    request: Request,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+enum Request {
+    /// Tabs and widgets with their values, and what is selected.
+    Tree,
+    Get { widget: WidgetRef },
+    /// Takes the same text form as the settings file and runs the callback of the widget.
+    Set { widget: WidgetRef, value: String },
+    /// Handled on the next frame as if the key was pressed.
+    Action { key: ActionKey },
+    Tab { tab: TabRef },
+    Notify {
+        text: String,
+        #[serde(default = "info")]
+        severity: Severity,
+        #[serde(default = "three_seconds")]
+        duration_ms: u64,
+    },
+    /// Sends every [`GuiEvent`] to this client from now on.
+    Subscribe,
+}
+
+fn info() -> Severity {
+    Severity::Info
+}
+
+fn three_seconds() -> u64 {
+    3000
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WidgetRef {
+    /// ID given with [`WidgetData::with_id`](crate::builders::WidgetData::with_id).
+    Id(String),
+    /// Tab and widget index.
+    Position(usize, usize),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TabRef {
+    Index(usize),
+    Name(String),
+}
+
+fn widget_json(index: usize, widget: &mut Widget) -> Value {
+    let state = widget.control.state();
+    let kind = state.as_ref().map(|state| match state {
+        WidgetState::Button => "button",
+        WidgetState::Toggle(..) => "toggle",
+        WidgetState::Slider(..) => "slider",
+        WidgetState::Radio(..) => "radio",
+        WidgetState::Color(..) => "color",
+        WidgetState::DateTime(_) => "date_time",
+        WidgetState::Binding(_) => "binding",
+    });
+    json!({
+        "index": index,
+        "label": widget.name,
+        "id": widget.id,
+        "kind": kind,
+        "value": state.and_then(|state| settings::encode(state)),
+        "enabled": widget.flags.is_enabled(),
+        "visible": widget.flags.is_visible(),
+    })
+}
+
+/// Applies `value` from a `set` request to `widget`. Radio buttons are only checked,
+/// turning one on is left to the tab as it turns the rest of the group off.
+fn set_value(widget: &mut Widget, value: &str) -> Result<(), String> {
+    if !widget.flags.is_enabled() {
+        return Err("Widget is disabled".to_string());
+    }
+    if widget.group.is_some() {
+        // radio buttons are only ever turned on, which turns off the others
+        if value != "true" {
+            return Err("Radio buttons can only be set to \"true\"".to_string());
+        }
+        return Ok(());
+    }
+    let Some(state) = widget.control.state() else { return Err("Widget has no value".to_string()) };
+    let mut new = state.clone();
+    if !settings::decode(&mut new, value) {
+        return Err(format!("Invalid value \"{value}\""));
+    }
+    if !widget.control.accepts(&new) {
+        return Err(format!("Value \"{value}\" is out of range"));
+    }
+    *widget.control.state().expect("checked above") = new;
+    widget.control.state_changed();
+    Ok(())
+}
+
+/// Refuses notifications that would show an empty toast.
+fn check_notification(text: &str) -> Result<(), String> {
+    match text.trim().is_empty() {
+        true => Err("Notification text is empty".to_string()),
+        false => Ok(()),
+    }
+}
+
+impl Gui {
+    /// Answers requests that came in since the last frame.
+    pub(crate) fn serve_control_socket(&mut self) {
+        let Some(socket) = &mut self.control_socket else { return };
+        let mut requests = Vec::new();
+        for incoming in socket.rx.try_iter() {
+            match incoming {
+                Incoming::Connected(id, tx) => {
+                    socket.clients.insert(id, Client { tx, subscribed: false });
+                },
+                Incoming::Line(id, line) => requests.push((id, line)),
+                Incoming::Disconnected(id) => {
+                    socket.clients.remove(&id);
+                },
+            }
+        }
+
+        for (client, line) in requests {
+            let reply = match serde_json::from_str::<Envelope>(&line) {
+                Ok(envelope) => {
+                    let mut reply = match self.handle_request(client, envelope.request) {
+                        Ok(mut body) => {
+                            body["ok"] = json!(true);
+                            body
+                        },
+                        Err(error) => json!({ "ok": false, "error": error }),
+                    };
+                    if let Some(id) = envelope.id {
+                        reply["id"] = id;
+                    }
+                    reply
+                },
+                Err(e) => json!({ "ok": false, "error": e.to_string() }),
+            };
+            if let Some(client) = self.control_socket.as_ref().and_then(|s| s.clients.get(&client)) {
+                let _ = client.tx.send(reply.to_string());
+            }
+        }
+    }
+    /// Sends `event` to all subscribed clients.
+    pub(crate) fn broadcast(&mut self, event: &GuiEvent) {
+        let Some(socket) = &mut self.control_socket else { return };
+        let message = match event {
+            GuiEvent::Quit => json!({ "event": "quit" }),
+            GuiEvent::FileChosen(path) => json!({ "event": "file_chosen", "path": path }),
+        }.to_string();
+        for client in socket.clients.values().filter(|c| c.subscribed) {
+            let _ = client.tx.send(message.clone());
+        }
+    }
+    fn find_widget(&self, widget: &WidgetRef) -> Result<(usize, usize), String> {
+        let found = match widget {
+            WidgetRef::Id(id) => self.tabs.iter()
+                .enumerate()
+                .filter_map(|(t, tab)| Some((t, tab.as_ref()?)))
+                .find_map(|(t, tab)| Some((t, tab.widgets.iter().position(|w| w.id.as_ref() == Some(id))?))),
+            WidgetRef::Position(t, w) => self.tabs.get(*t)
+                .and_then(Option::as_ref)
+                .filter(|tab| *w < tab.widgets.len())
+                .map(|_| (*t, *w)),
+        };
+        found.ok_or_else(|| "No such widget".to_string())
+    }
+    fn handle_request(&mut self, client: u64, request: Request) -> Result<Value, String> {
+        Ok(match request {
+            Request::Tree => {
+                let tabs = self.tabs.iter_mut()
+                    .enumerate()
+                    .filter_map(|(i, tab)| Some((i, tab.as_mut()?)))
+                    .map(|(i, tab)| json!({
+                        "index": i,
+                        "name": tab.name,
+                        "widgets": tab.widgets.iter_mut().enumerate().map(|(i, w)| widget_json(i, w)).collect::<Vec<_>>(),
+                    }))
+                    .collect::<Vec<_>>();
+                let focus = match self.focus {
+                    Focus::TabBar => "tab_bar",
+                    Focus::Widgets => "widgets",
+                    Focus::WidgetSingle => "widget_single",
+                };
+                json!({
+                    "tabs": tabs,
+                    "current_tab": self.current_tab,
+                    "current_widget": self.current_widget,
+                    "focus": focus,
+                })
+            },
+            Request::Get { widget } => {
+                let (t, w) = self.find_widget(&widget)?;
+                let widget = &mut self.tabs[t].as_mut().expect("found above").widgets[w];
+                widget_json(w, widget)
+            },
+            Request::Set { widget, value } => {
+                let (t, w) = self.find_widget(&widget)?;
+                let tab = self.tabs[t].as_mut().expect("found above");
+                set_value(&mut tab.widgets[w], &value)?;
+                if tab.widgets[w].group.is_some() {
+                    tab.select_radio(w);
+                }
+                json!({})
+            },
+            Request::Action { key } => {
+                self.injected.push_back(key);
+                json!({})
+            },
+            Request::Tab { tab } => {
+                let index = match tab {
+                    TabRef::Index(i) => Some(i).filter(|i| self.tabs.get(*i).is_some_and(Option::is_some)),
+                    TabRef::Name(name) => self.tabs.iter().position(|t| t.as_ref().is_some_and(|t| t.name == name)),
+                };
+                let index = index.ok_or_else(|| "No such tab".to_string())?;
+                if self.focus == Focus::WidgetSingle {
+                    let curtab = self.tabs[self.current_tab].as_mut().expect("current_tab should always be a valid index for a tab");
+                    if let Some(curwdg) = curtab.widgets.get_mut(self.current_widget) {
+                        curwdg.control.set_editing(false);
+                    }
+                    self.focus.bump_up();
+                }
+                self.current_tab = index;
+                self.current_widget = 0;
+                self.want_widget_scroll = 0;
+                json!({})
+            },
+            Request::Notify { text, severity, duration_ms } => {
+                check_notification(&text)?;
+                self.toasts.push(text, Duration::from_millis(duration_ms), severity);
+                json!({})
+            },
+            Request::Subscribe => {
+                if let Some(client) = self.control_socket.as_mut().and_then(|s| s.clients.get_mut(&client)) {
+                    client.subscribed = true;
+                }
+                json!({})
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::{Radio, Slider, Toggle};
+
+    fn parse(line: &str) -> Result<Envelope, serde_json::Error> {
+        serde_json::from_str(line)
+    }
+
+    #[test]
+    fn widgets_by_id_or_position() {
+        let envelope = parse(r#"{"cmd": "get", "widget": "wifi", "id": 7}"#).expect("should parse");
+        assert_eq!(envelope.id, Some(json!(7)));
+        assert!(matches!(envelope.request, Request::Get { widget: WidgetRef::Id(id) } if id == "wifi"));
+        let envelope = parse(r#"{"cmd": "set", "widget": [0, 2], "value": "true"}"#).expect("should parse");
+        assert!(envelope.id.is_none());
+        assert!(matches!(envelope.request, Request::Set { widget: WidgetRef::Position(0, 2), value } if value == "true"));
+    }
+
+    #[test]
+    fn tabs_by_index_or_name() {
+        assert!(matches!(parse(r#"{"cmd": "tab", "tab": 3}"#).map(|e| e.request), Ok(Request::Tab { tab: TabRef::Index(3) })));
+        assert!(matches!(parse(r#"{"cmd": "tab", "tab": "Network"}"#).map(|e| e.request), Ok(Request::Tab { tab: TabRef::Name(name) }) if name == "Network"));
+    }
+
+    #[test]
+    fn notify_defaults() {
+        let request = parse(r#"{"cmd": "notify", "text": "Done"}"#).map(|e| e.request);
+        assert!(matches!(request, Ok(Request::Notify { text, severity: Severity::Info, duration_ms: 3000 }) if text == "Done"));
+        let request = parse(r#"{"cmd": "notify", "text": "Oops", "severity": "error", "duration_ms": 10}"#).map(|e| e.request);
+        assert!(matches!(request, Ok(Request::Notify { severity: Severity::Error, duration_ms: 10, .. })));
+    }
+
+    #[test]
+    fn simple_requests() {
+        assert!(matches!(parse(r#"{"cmd": "tree"}"#).map(|e| e.request), Ok(Request::Tree)));
+        assert!(matches!(parse(r#"{"cmd": "subscribe"}"#).map(|e| e.request), Ok(Request::Subscribe)));
+        assert!(matches!(parse(r#"{"cmd": "action", "key": "page_down"}"#).map(|e| e.request), Ok(Request::Action { key: ActionKey::PageDown })));
+    }
+
+    #[test]
+    fn malformed_requests() {
+        for line in [
+            r#"{"cmd": "reboot"}"#,
+            r#"{"widget": "wifi"}"#,
+            r#"{"cmd": "get"}"#,
+            r#"{"cmd": "set", "widget": "wifi", "value": true}"#,
+            r#"{"cmd": "action", "key": "jump"}"#,
+            r#"{"cmd": "notify"}"#,
+            "not json",
+        ] {
+            assert!(parse(line).is_err(), "{line}");
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("sgui2-test-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn bind_replaces_stale_sockets() {
+        let path = temp_path("stale.sock");
+        drop(UnixListener::bind(&path).expect("Failed to bind test socket"));
+        assert!(path.exists());
+        let socket = ControlSocket::bind(path.clone());
+        assert!(socket.is_ok());
+        drop(socket);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn bind_leaves_live_sockets_and_other_files_alone() {
+        let path = temp_path("live.sock");
+        let first = ControlSocket::bind(path.clone()).expect("Failed to bind test socket");
+        assert!(ControlSocket::bind(path.clone()).is_err());
+        assert!(UnixStream::connect(&path).is_ok());
+        drop(first);
+
+        let path = temp_path("file.sock");
+        fs::write(&path, "important").expect("Failed to write test file");
+        let result = ControlSocket::bind(path.clone());
+        let content = fs::read_to_string(&path);
+        let _ = fs::remove_file(&path);
+        assert!(result.is_err());
+        assert_eq!(content.ok().as_deref(), Some("important"));
+    }
+
+    fn widget(control: impl crate::Control + 'static) -> Widget {
+        Widget {
+            name: "Test".to_string(),
+            text: None,
+            control: Box::new(control),
+            group: None,
+            icon: None,
+            flags: Default::default(),
+            description: None,
+            id: None,
+        }
+    }
+
+    fn value(widget: &mut Widget) -> Option<String> {
+        settings::encode(widget.control.state()?)
+    }
+
+    #[test]
+    fn set_refuses_values_out_of_range() {
+        let mut slider = widget(Slider::new(None, 50).with_range(20, 200));
+        assert_eq!(set_value(&mut slider, "100"), Ok(()));
+        assert_eq!(set_value(&mut slider, "250"), Err("Value \"250\" is out of range".to_string()));
+        assert_eq!(set_value(&mut slider, "10"), Err("Value \"10\" is out of range".to_string()));
+        assert_eq!(set_value(&mut slider, "loud"), Err("Invalid value \"loud\"".to_string()));
+        assert_eq!(value(&mut slider).as_deref(), Some("100"));
+    }
+
+    #[test]
+    fn set_refuses_disabled_widgets() {
+        let mut toggle = widget(Toggle::new(None, false));
+        toggle.flags.set_enabled(false);
+        assert_eq!(set_value(&mut toggle, "true"), Err("Widget is disabled".to_string()));
+        assert_eq!(value(&mut toggle).as_deref(), Some("false"));
+    }
+
+    #[test]
+    fn set_only_turns_radio_buttons_on() {
+        let mut radio = widget(Radio::new(true));
+        radio.group = Some(0);
+        assert_eq!(set_value(&mut radio, "false"), Err("Radio buttons can only be set to \"true\"".to_string()));
+        assert_eq!(set_value(&mut radio, "true"), Ok(()));
+        assert_eq!(value(&mut radio).as_deref(), Some("true"));
+    }
+
+    #[test]
+    fn notify_refuses_empty_text() {
+        assert!(check_notification("").is_err());
+        assert!(check_notification(" \n\t").is_err());
+        assert_eq!(check_notification("Done"), Ok(()));
+    }
+}
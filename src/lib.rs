@@ -1,17 +1,55 @@
 pub mod builders;
 mod theme;
+mod toast;
+mod image;
+mod text;
+mod log;
+mod browser;
+mod control;
+mod graph;
+mod color;
+mod datetime;
+mod capture;
+mod command;
+mod footer;
+mod hints;
+mod status;
+mod settings;
+mod definition;
+mod ipc;
 
-use theme::Theme;
+pub use toast::{Notifier, Severity};
+pub use image::ImageSource;
+pub use log::{LogLine, LogStream};
+pub use browser::FileBrowser;
+pub use control::{Context, Control};
+pub use graph::{Graph, GraphFeed, GraphStyle};
+pub use datetime::{DateTime, DateTimeFields};
+pub use capture::Binding;
+pub use status::{StatusBar, StatusHandle};
+pub use definition::{Callbacks, DefinitionError, Format};
+pub use theme::Theme;
+
+use toast::Toasts;
+use image::ImageCache;
+use footer::Footer;
+use hints::{Glyphs, HintBar};
+use settings::Settings;
+use ipc::ControlSocket;
 use derivative::Derivative;
+use serde::Deserialize;
 use sdl2::{
     render::{
         self,
         Texture,
+        TextureCreator,
     },
-    video::{self},
+    video::{self, WindowContext},
+    ttf::Font,
     rect::Rect,
     event::{Event, WindowEvent},
-    keyboard::Keycode,
+    keyboard::{Keycode, Mod},
+    controller::{Button, GameController},
 };
 use std::time::{
     Instant,
@@ -19,7 +57,11 @@ use std::time::{
 };
 use std::ops::{Add, Sub, Div};
 use std::cmp::PartialOrd;
-use std::cell::Cell;
+use std::rc::Rc;
+use std::path::PathBuf;
+use std::mem;
+use std::collections::VecDeque;
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
 
 fn closerize<T: Add<Output = T> + Sub<Output = T> + Div<T, Output = T> + PartialOrd<i32> + Copy + From<u8>>(true_value: T, target: T) -> T {
     let diff = true_value - target;
@@ -34,9 +76,47 @@ fn closerize<T: Add<Output = T> + Sub<Output = T> + Div<T, Output = T> + Partial
     true_value - (true_value - target).div(div.into())
 }
 
+/// With unsafe_textures textures are only freed together with the canvas,
+/// so anything replaced while the Gui is running has to be destroyed by hand.
+fn destroy_texture(texture: Texture) {
+    // SAFETY: only called while Gui, and so the canvas, is alive
+    unsafe { texture.destroy() };
+}
+
+fn fill_circle(canvas: &mut render::Canvas<video::Window>, cx: i32, cy: i32, r: i32) -> Result<(), String> {
+    for dy in -r..=r {
+        let dx = ((r * r - dy * dy) as f32).sqrt() as i32;
+        canvas.draw_line((cx - dx, cy + dy), (cx + dx, cy + dy))?;
+    }
+    Ok(())
+}
+
+fn draw_circle(canvas: &mut render::Canvas<video::Window>, cx: i32, cy: i32, r: i32) -> Result<(), String> {
+    // midpoint circle algorithm
+    let (mut x, mut y, mut err) = (r, 0, 1 - r);
+    while x >= y {
+        canvas.draw_points(&[
+            (cx + x, cy + y).into(), (cx + y, cy + x).into(),
+            (cx - y, cy + x).into(), (cx - x, cy + y).into(),
+            (cx - x, cy - y).into(), (cx - y, cy - x).into(),
+            (cx + y, cy - x).into(), (cx + x, cy - y).into(),
+        ][..])?;
+        y += 1;
+        if err < 0 {
+            err += 2 * y + 1;
+        } else {
+            x -= 1;
+            err += 2 * (y - x) + 1;
+        }
+    }
+    Ok(())
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum GuiEvent {
     Quit,
+    /// A file was picked in a [`FileBrowser`] tab.
+    FileChosen(PathBuf),
 }
 
 #[derive(Derivative)]
@@ -46,6 +126,10 @@ pub struct Gui {
     canvas: render::Canvas<video::Window>,
     #[derivative(Debug="ignore")]
     event_pump: sdl2::EventPump,
+    #[derivative(Debug="ignore")]
+    texture_creator: TextureCreator<WindowContext>,
+    #[derivative(Debug="ignore")]
+    font: Font<'static, 'static>,
     tabs: Vec<Option<Tab>>,
     current_tab: usize,
     current_widget: usize,
@@ -57,6 +141,40 @@ pub struct Gui {
     selection: SelectionWindow,
     want_widget_scroll: i32,
     widget_scroll: i32,
+    want_tab_bar_scroll: i32,
+    tab_bar_scroll: i32,
+    // top of every widget of the current tab as drawn last, for paging
+    widget_rows: Vec<i32>,
+    page_height: u32,
+    // start of every tab along the tab bar and the length shown, for paging
+    tab_starts: Vec<i32>,
+    tab_page: u32,
+    // how far into the selected row the panel shows, for rows taller than it
+    row_offset: u32,
+    row_offset_of: (usize, usize),
+    selected_height: u32,
+    toasts: Toasts,
+    images: ImageCache,
+    #[derivative(Debug="ignore")]
+    game_controller: sdl2::GameControllerSubsystem,
+    // closed when dropped
+    #[derivative(Debug="ignore")]
+    controllers: Vec<GameController>,
+    // fraction of the window, None fits the tab names
+    tab_bar_fraction: Option<f32>,
+    tab_bar_position: TabBarPosition,
+    #[derivative(Debug="ignore")]
+    footer: Footer,
+    #[derivative(Debug="ignore")]
+    hint_bar: Option<HintBar>,
+    glyphs: Glyphs,
+    #[derivative(Debug="ignore")]
+    status_bar: Option<StatusBar>,
+    settings: Option<Settings>,
+    #[derivative(Debug="ignore")]
+    control_socket: Option<ControlSocket>,
+    // actions sent through the control socket, handled one per frame
+    injected: VecDeque<ActionKey>,
 }
 
 #[derive(Debug)]
@@ -95,7 +213,8 @@ impl SelectionWindow {
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ActionKey {
     None,
     Press,
@@ -104,18 +223,189 @@ pub enum ActionKey {
     Down,
     Left,
     Right,
+    /// Moves the selection by a screenful, through the tabs while the tab bar is focused.
+    PageUp,
+    PageDown,
+    /// First or last widget, or tab while the tab bar is focused.
+    Home,
+    End,
+    /// Switches tabs from any focus level.
+    PrevTab,
+    NextTab,
 }
 
 impl Gui {
+    /// Shows a non-blocking toast in the bottom right corner.
+    pub fn notify(&mut self, text: impl ToString, duration: Duration, severity: Severity) {
+        self.toasts.push(text, duration, severity);
+    }
+    /// Returns a handle that can show toasts from callbacks or other threads.
+    pub fn notifier(&self) -> Notifier {
+        self.toasts.notifier()
+    }
+    /// Current values of widgets and radio groups with an ID, in the same text form as the settings file.
+    pub fn values(&mut self) -> Vec<(String, String)> {
+        let mut values = self.tabs.iter_mut().flatten()
+            .flat_map(|t| t.widgets.iter_mut())
+            .filter_map(|widget| {
+                let id = widget.id.clone()?;
+                let value = settings::encode(widget.control.state()?)?;
+                Some((id, value))
+            })
+            .collect::<Vec<_>>();
+        values.extend(self.tabs.iter_mut().flatten().flat_map(Tab::radio_values));
+        values
+    }
+    /// Where the tab bar goes on the current window, None when tabs and widgets are shown one at a time.
+    fn tab_bar_position(&self) -> Option<TabBarPosition> {
+        let (width, height) = self.window_size;
+        match self.tab_bar_position {
+            TabBarPosition::Auto if height > width => None,
+            TabBarPosition::Auto if width < 641 => Some(TabBarPosition::Top),
+            TabBarPosition::Auto => Some(TabBarPosition::Left),
+            position => Some(position),
+        }
+    }
+    /// Actions available with the current focus, for the hint bar.
+    fn hints(&self) -> Vec<(&'static str, &'static str)> {
+        let g = self.glyphs;
+        let curtab = self.tabs.get(self.current_tab).unwrap().as_ref().unwrap();
+        let mut hints = Vec::new();
+        match self.focus {
+            Focus::TabBar => {
+                let horizontal = matches!(self.tab_bar_position(), Some(TabBarPosition::Top | TabBarPosition::Bottom));
+                hints.push((if horizontal { "\u{2190}\u{2192}" } else { "\u{2191}\u{2193}" }, "Tab"));
+                if !curtab.widgets.is_empty() {
+                    hints.push((g.press(), "Open"));
+                }
+            },
+            Focus::Widgets => {
+                hints.push((if curtab.layout == Layout::Grid { "Arrows" } else { "\u{2191}\u{2193}" }, "Move"));
+                if let Some(widget) = curtab.widgets.get(self.current_widget) {
+                    hints.push((g.press(), if widget.grabs_input() { "Edit" } else { "Select" }));
+                }
+                hints.push((g.back(), "Back"));
+                hints.push((g.switch_tab(), "Switch tab"));
+            },
+            Focus::WidgetSingle => match curtab.widgets.get(self.current_widget) {
+                Some(widget) => hints = widget.control.hints(g.back()),
+                None => hints.push((g.back(), "Done")),
+            },
+        }
+        hints
+    }
+    /// Moves to the previous or next tab, skipping separators.
+    fn switch_tab(&mut self, forward: bool) {
+        let maybe_new_tab = if forward {
+            self.tabs.iter()
+                .enumerate()
+                .skip(self.current_tab + 1)
+                .find_map(|(i, v)| v.as_ref().and(Some(i)))
+        } else {
+            self.tabs.iter()
+                .enumerate()
+                .rev()
+                .skip_while(|(i, _)| *i+1 != self.current_tab)
+                .find_map(|(i, v)| v.as_ref().and(Some(i)))
+        };
+        if let Some(new_tab) = maybe_new_tab {
+            self.current_tab = new_tab;
+            self.current_widget = 0;
+        }
+    }
+    /// Width of the tab bar in the two panel layout, as set by the builder or else
+    /// fitting the widest tab within the limits set by [`Theme`].
+    fn tab_bar_width(&self, width: u32) -> u32 {
+        if let Some(fraction) = self.tab_bar_fraction {
+            return (width as f32 * fraction) as u32;
+        }
+        let pad = (Theme::padding() as u32 * self.font_height as u32) / 100;
+        let fit = self.tabs.iter().flatten().map(|tab| {
+            let q = tab.text.query();
+            let icon_width = tab.icon.as_ref()
+                .map(|icon| image::scaled_to_height(icon, q.height).0 + pad)
+                .unwrap_or(0);
+            q.width + icon_width + pad*2
+        }).max().unwrap_or(0);
+        let min = (width as f32 * Theme::tab_bar_min()) as u32;
+        let max = (width as f32 * Theme::tab_bar_max()) as u32;
+        fit.clamp(min, max)
+    }
     pub fn tick(&mut self) -> Option<GuiEvent> {
+        self.serve_control_socket();
+        let event = self.frame();
+        if let Some(event) = &event {
+            self.broadcast(event);
+        }
+        event
+    }
+    fn frame(&mut self) -> Option<GuiEvent> {
         self.canvas.set_viewport(None);
         self.canvas.clear();
 
+        for widget in self.tabs.iter_mut().flatten().flat_map(|t| t.widgets.iter_mut()) {
+            if let Some(error) = widget.poll() {
+                self.toasts.push(error, Theme::command_error_duration(), Severity::Error);
+            }
+        }
+
+        let curtab = self.tabs.get_mut(self.current_tab).unwrap().as_mut()
+            .expect("current_tab should always be a valid index for a tab");
+        if let Some((widgets, selected)) = curtab.refresh(&self.font, &self.texture_creator, &mut self.images) {
+            self.current_widget = selected;
+            self.want_widget_scroll = 0;
+            if widgets.is_empty() && self.focus != Focus::TabBar {
+                self.focus = Focus::TabBar;
+            }
+            for old in mem::replace(&mut curtab.widgets, widgets) {
+                old.destroy();
+            }
+        }
+
+        let mut event = None;
         let mut action = ActionKey::None;
 
         for event in self.event_pump.poll_iter() {
+            match event {
+                Event::KeyDown{..} => self.glyphs = Glyphs::Keyboard,
+                Event::ControllerButtonDown{which, ..} => {
+                    if let Some(controller) = self.controllers.iter().find(|c| c.instance_id() == which) {
+                        self.glyphs = Glyphs::from_controller_name(&controller.name());
+                    }
+                },
+                _ => (),
+            }
+            if self.focus == Focus::WidgetSingle {
+                let curtab = self.tabs.get_mut(self.current_tab).unwrap().as_mut().unwrap();
+                if let Some(curwdg) = curtab.widgets.get_mut(self.current_widget) {
+                    if curwdg.control.raw_event(&event) {
+                        continue;
+                    }
+                }
+            }
             match event {
                 Event::Quit{..} => return Some(GuiEvent::Quit),
+                Event::ControllerDeviceAdded{which, ..} => {
+                    if let Ok(controller) = self.game_controller.open(which) {
+                        self.controllers.push(controller);
+                    }
+                    continue;
+                },
+                Event::ControllerDeviceRemoved{which, ..} => {
+                    self.controllers.retain(|c| c.instance_id() != which);
+                    continue;
+                },
+                Event::ControllerButtonDown{button, ..} => match button {
+                    Button::A         => action = ActionKey::Press,
+                    Button::B         => action = ActionKey::Back,
+                    Button::DPadUp    => action = ActionKey::Up,
+                    Button::DPadDown  => action = ActionKey::Down,
+                    Button::DPadLeft  => action = ActionKey::Left,
+                    Button::DPadRight => action = ActionKey::Right,
+                    Button::LeftShoulder  => action = ActionKey::PrevTab,
+                    Button::RightShoulder => action = ActionKey::NextTab,
+                    _ => continue,
+                },
                 Event::Window{win_event, ..} => match win_event {
                     WindowEvent::SizeChanged(..) => {
                         let tmp = self.canvas.viewport();
@@ -123,7 +413,7 @@ impl Gui {
                     },
                     _ => continue,
                 },
-                Event::KeyDown{keycode, repeat, ..} => {
+                Event::KeyDown{keycode, keymod, repeat, ..} => {
                     if repeat {
                         //continue;
                     }
@@ -135,6 +425,12 @@ impl Gui {
                             Keycode::Down   => action = ActionKey::Down,
                             Keycode::Left   => action = ActionKey::Left,
                             Keycode::Right  => action = ActionKey::Right,
+                            Keycode::PageUp   => action = ActionKey::PageUp,
+                            Keycode::PageDown => action = ActionKey::PageDown,
+                            Keycode::Home     => action = ActionKey::Home,
+                            Keycode::End      => action = ActionKey::End,
+                            Keycode::Tab if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) => action = ActionKey::PrevTab,
+                            Keycode::Tab      => action = ActionKey::NextTab,
                             _ => continue,
                         }
                     }
@@ -143,6 +439,20 @@ impl Gui {
             }
             break;
         }
+        if action == ActionKey::None {
+            action = self.injected.pop_front().unwrap_or(ActionKey::None);
+        }
+
+        if self.focus == Focus::WidgetSingle {
+            let curtab = self.tabs.get_mut(self.current_tab).unwrap().as_mut().unwrap();
+            if let Some(curwdg) = curtab.widgets.get_mut(self.current_widget) {
+                if curwdg.control.editing_done() {
+                    curwdg.control.set_editing(false);
+                    self.focus.bump_up();
+                    self.last_interaction = Instant::now();
+                }
+            }
+        }
 
         if action != ActionKey::None {
             self.last_interaction = Instant::now();
@@ -160,47 +470,79 @@ impl Gui {
                 } else {
                     let curtab = self.tabs.get_mut(self.current_tab).unwrap().as_mut()
                         .expect("current_tab should always be a valid index for a tab");
+                    let mut enabled = false;
                     if let Some(curwdg) = curtab.widgets.get_mut(self.current_widget) {
                         if curwdg.grabs_input() {
                             self.focus.bump_down();
+                            curwdg.control.set_editing(self.focus == Focus::WidgetSingle);
                         }
                         curwdg.process_action(&action);
+                        enabled = curwdg.flags.is_enabled();
+                        if enabled && curwdg.group.is_some() {
+                            curtab.select_radio(self.current_widget);
+                        }
+                    }
+                    if let Some(browser) = curtab.browser.as_mut().filter(|_| enabled) {
+                        event = browser.activate(self.current_widget).map(GuiEvent::FileChosen);
                     }
                 }
             },
             ActionKey::Back => {
-                self.focus.bump_up();
+                let curtab = self.tabs.get_mut(self.current_tab).unwrap().as_mut()
+                    .expect("current_tab should always be a valid index for a tab");
+                let went_up = match &mut curtab.browser {
+                    Some(browser) if self.focus == Focus::Widgets => browser.back(),
+                    _ => false,
+                };
+                if !went_up {
+                    if self.focus == Focus::WidgetSingle {
+                        if let Some(curwdg) = curtab.widgets.get_mut(self.current_widget) {
+                            curwdg.control.set_editing(false);
+                        }
+                    }
+                    self.focus.bump_up();
+                }
             },
             ActionKey::Up | ActionKey::Down | ActionKey::Left | ActionKey::Right => {
                 match self.focus {
                     Focus::TabBar => {
-                        let maybe_new_tab = match action {
-                            ActionKey::Up => self.tabs.iter()
-                                .enumerate()
-                                .rev()
-                                .skip_while(|(i, _)| *i+1 != self.current_tab)
-                                .find_map(|(i, v)| v.as_ref().and(Some(i))),
-                            ActionKey::Down => self.tabs.iter()
-                                .enumerate()
-                                .skip(self.current_tab + 1)
-                                .find_map(|(i, v)| v.as_ref().and(Some(i))),
-                            _ => None,
-                        };
-                        if let Some(new_tab) = maybe_new_tab {
-                            self.current_tab = new_tab;
-                            self.current_widget = 0;
+                        let horizontal = matches!(self.tab_bar_position(), Some(TabBarPosition::Top | TabBarPosition::Bottom));
+                        match (action, horizontal) {
+                            (ActionKey::Up, false) | (ActionKey::Left, true) => self.switch_tab(false),
+                            (ActionKey::Down, false) | (ActionKey::Right, true) => self.switch_tab(true),
+                            _ => (),
                         }
                     },
                     Focus::Widgets => {
-                        let diff: i32 = match action {
-                            ActionKey::Up => -1,
-                            ActionKey::Down => 1,
-                            _ => 0,
-                        };
                         let curtab = self.tabs.get(self.current_tab).unwrap().as_ref().unwrap();
-                        let new = (self.current_widget as i32 + diff).clamp(0, i32::MAX) as usize;
-                        if curtab.widgets.get(new).is_some() {
-                            self.current_widget = new;
+                        if curtab.layout == Layout::Grid {
+                            self.current_widget = curtab.grid_move(self.current_widget, action);
+                        } else {
+                            let diff: i32 = match action {
+                                ActionKey::Up => -1,
+                                ActionKey::Down => 1,
+                                _ => 0,
+                            };
+                            // a row taller than the panel is scrolled through before moving on
+                            let within = match diff {
+                                0 => None,
+                                _ => scroll_within(self.row_offset, self.selected_height, self.page_height, self.page_height / 2, diff > 0),
+                            };
+                            if let Some(offset) = within {
+                                self.row_offset = offset;
+                            }
+                            let mut new = self.current_widget as i32 + diff;
+                            while within.is_none() && diff != 0 && new >= 0 {
+                                let Some(widget) = curtab.widgets.get(new as usize) else { break };
+                                if widget.focusable() {
+                                    self.current_widget = new as usize;
+                                    // going up enters a tall row at its bottom
+                                    self.row_offset = if diff < 0 { u32::MAX } else { 0 };
+                                    self.row_offset_of = (self.current_tab, self.current_widget);
+                                    break;
+                                }
+                                new += diff;
+                            }
                         }
                     },
                     Focus::WidgetSingle => {
@@ -213,9 +555,98 @@ impl Gui {
                     },
                 }
             }
+            ActionKey::PrevTab | ActionKey::NextTab => {
+                if self.focus == Focus::WidgetSingle {
+                    let curtab = self.tabs.get_mut(self.current_tab).unwrap().as_mut().unwrap();
+                    if let Some(curwdg) = curtab.widgets.get_mut(self.current_widget) {
+                        curwdg.control.set_editing(false);
+                    }
+                    self.focus.bump_up();
+                }
+                self.switch_tab(action == ActionKey::NextTab);
+                self.want_widget_scroll = 0;
+                let curtab = self.tabs.get(self.current_tab).unwrap().as_ref().unwrap();
+                if curtab.widgets.is_empty() {
+                    self.focus = Focus::TabBar;
+                }
+            },
+            ActionKey::PageUp | ActionKey::PageDown | ActionKey::Home | ActionKey::End => {
+                match self.focus {
+                    Focus::TabBar => {
+                        let exists = |i: usize| self.tabs[i].is_some();
+                        let count = self.tabs.len();
+                        let new_tab = match action {
+                            ActionKey::Home => (0..count).find(|i| exists(*i)),
+                            ActionKey::End => (0..count).rev().find(|i| exists(*i)),
+                            _ => page_move(&self.tab_starts, count, self.tab_page, self.current_tab, action == ActionKey::PageDown, exists),
+                        };
+                        if let Some(new_tab) = new_tab {
+                            self.current_tab = new_tab;
+                            self.current_widget = 0;
+                        }
+                    },
+                    Focus::Widgets => {
+                        let curtab = self.tabs.get(self.current_tab).unwrap().as_ref().unwrap();
+                        let focusable = |i: usize| curtab.widgets[i].focusable();
+                        let count = curtab.widgets.len();
+                        let new = match action {
+                            ActionKey::Home => (0..count).find(|i| focusable(*i)),
+                            ActionKey::End => (0..count).rev().find(|i| focusable(*i)),
+                            _ => page_move(&self.widget_rows, count, self.page_height, self.current_widget, action == ActionKey::PageDown, focusable),
+                        };
+                        if let Some(new) = new {
+                            self.current_widget = new;
+                        }
+                    },
+                    Focus::WidgetSingle => {
+                        let curtab = self.tabs.get_mut(self.current_tab).unwrap().as_mut().unwrap();
+                        if let Some(curwdg) = curtab.widgets.get_mut(self.current_widget) {
+                            curwdg.process_action(&action);
+                        }
+                    },
+                }
+            },
             ActionKey::None => (),
         }
 
+        if self.focus != Focus::TabBar {
+            let curtab = self.tabs.get_mut(self.current_tab).unwrap().as_mut().unwrap();
+            let current = self.current_widget;
+            if !curtab.widgets.get(current).is_some_and(|w| w.focusable()) {
+                // hidden or disabled from a callback, or never focusable to begin with
+                if let Some(curwdg) = curtab.widgets.get_mut(current) {
+                    curwdg.control.set_editing(false);
+                }
+                let nearest = (current..curtab.widgets.len())
+                    .chain((0..current).rev())
+                    .find(|i| curtab.widgets[*i].focusable());
+                match nearest {
+                    Some(i) => {
+                        self.current_widget = i;
+                        self.focus = Focus::Widgets;
+                    },
+                    None => self.focus = Focus::TabBar,
+                }
+            }
+        }
+
+        if let Some(settings) = &mut self.settings {
+            for widget in self.tabs.iter_mut().flatten().flat_map(|t| t.widgets.iter_mut()) {
+                let Some(id) = &widget.id else { continue };
+                if let Some(value) = widget.control.state().and_then(|state| settings::encode(state)) {
+                    settings.set(id, value);
+                }
+            }
+            for (id, value) in self.tabs.iter_mut().flatten().flat_map(Tab::radio_values) {
+                settings.set(&id, value);
+            }
+            settings.set("sgui2.tab", self.current_tab.to_string());
+            settings.set("sgui2.widget", self.current_widget.to_string());
+            if let Err(error) = settings.flush() {
+                self.toasts.push(error, Theme::command_error_duration(), Severity::Error);
+            }
+        }
+
         if self.last_interaction.elapsed() > Theme::idle_timeout() {
             // idle animations
             let reset;
@@ -226,99 +657,210 @@ impl Gui {
         }
 
         let (width, height) = self.window_size;
+
+        let curtab = self.tabs.get(self.current_tab).unwrap().as_ref().unwrap();
+        let description = match self.focus {
+            Focus::TabBar => curtab.description.as_deref(),
+            Focus::Widgets | Focus::WidgetSingle => curtab.widgets.get(self.current_widget)
+                .and_then(|w| w.description.as_deref()),
+        };
+        self.footer.tick(description, &self.font, &self.texture_creator, width);
+        let hint_height = if self.hint_bar.is_some() { HintBar::height(self.font_height) } else { 0 };
+        let status_height = if self.status_bar.is_some() { StatusBar::height(self.font_height) } else { 0 };
+        let height = height.saturating_sub(self.footer.height() + hint_height + status_height);
         
-        let one_panel: bool = height > width || width < 641;
-        let left_panel;
+        let pad = (Theme::padding() as i32 * self.font_height)  / 100;
+        let strip_height = (self.font_height + pad*2) as u32;
+        let mut left_panel = None;
+        let mut strip_panel = None;
         let right_panel;
-        if one_panel {
-            let mut old_viewport = self.canvas.viewport();
-            match self.focus {
-                Focus::TabBar => {
-                    old_viewport.set_y(0);
-                    left_panel = Some(old_viewport);
-                    right_panel = None;
-                },
-                Focus::Widgets | Focus::WidgetSingle => {
-                    old_viewport.set_y(self.widget_scroll);
-                    left_panel = None;
-                    right_panel = Some(old_viewport);
+        // top edge of the widget area, before scrolling
+        let mut top = status_height as i32;
+        match self.tab_bar_position() {
+            None => {
+                let mut old_viewport = self.canvas.viewport();
+                old_viewport.set_height(height);
+                match self.focus {
+                    Focus::TabBar => {
+                        old_viewport.set_y(top);
+                        left_panel = Some(old_viewport);
+                        right_panel = None;
+                    },
+                    Focus::Widgets | Focus::WidgetSingle => {
+                        old_viewport.set_y(top + self.widget_scroll);
+                        right_panel = Some(old_viewport);
+                    }
                 }
-            }
-        } else {
-            // 20:80 ratio, TODO: make this smarter
-            let sep = width / 5; // separator
-            let r_width = width - sep;
-            let l_width = width - r_width;
-            left_panel = Some(Rect::new(0, 0, l_width, height));
-            right_panel = Some(Rect::new(sep as i32, self.widget_scroll, r_width, height));
+            },
+            Some(TabBarPosition::Top) => {
+                strip_panel = Some(Rect::new(0, top, width, strip_height));
+                top += strip_height as i32;
+                right_panel = Some(Rect::new(0, top + self.widget_scroll, width, height.saturating_sub(strip_height)));
+            },
+            Some(TabBarPosition::Bottom) => {
+                strip_panel = Some(Rect::new(0, top + height.saturating_sub(strip_height) as i32, width, strip_height));
+                right_panel = Some(Rect::new(0, top + self.widget_scroll, width, height.saturating_sub(strip_height)));
+            },
+            Some(_) => {
+                let sep = self.tab_bar_width(width); // separator
+                let r_width = width - sep;
+                let l_width = width - r_width;
+                left_panel = Some(Rect::new(0, top, l_width, height));
+                right_panel = Some(Rect::new(sep as i32, top + self.widget_scroll, r_width, height));
+            },
         }
 
         if let Some(left) = left_panel {
             self.canvas.set_draw_color(Theme::bg_tabs());
             self.canvas.fill_rect(left).expect("Failed to clear left side");
             self.canvas.set_viewport(left);
-            let mut y_pos = 0;
-            let pad = (Theme::padding() as i32 * self.font_height)  / 100;
+            let mut y_pos = self.tab_bar_scroll;
+            self.tab_starts.clear();
+            self.tab_page = left.height();
             for (i, t) in self.tabs.iter().enumerate() {
                 y_pos += pad;
+                self.tab_starts.push(y_pos - self.tab_bar_scroll);
                 if let Some(tab) = t {
                     let q = tab.text.query();
+                    let icon_size = tab.icon.as_ref().map(|icon| image::scaled_to_height(icon, q.height));
+                    let icon_width = icon_size.map(|(w, _)| w + pad as u32).unwrap_or(0);
                     let mut out_rect = Rect::new(pad, y_pos, q.width, q.height);
+                    if i == self.current_tab {
+                        let h = q.height + pad as u32;
+                        let y = y_pos - pad/2;
+                        // keep the current tab visible even while the widgets are focused
+                        if y + h as i32 > left.height() as i32 {
+                            self.want_tab_bar_scroll -= y + h as i32 - left.height() as i32;
+                        }
+                        if y < 0 {
+                            self.want_tab_bar_scroll -= y;
+                        }
+                    }
                     if i == self.current_tab && self.focus == Focus::TabBar {
-                        if q.width > left.width() {
-                            let scroll_max = q.width - left.width() + pad as u32;
+                        if q.width + icon_width > left.width() {
+                            let scroll_max = q.width + icon_width - left.width() + pad as u32;
                             let scroll = (self.tab_scroll.clamp(0, 128) as f32)/128.0 * scroll_max as f32;
                             out_rect.set_x(pad + (-1 * scroll as i32));
                         }
-                        let w = left.width() - pad as u32;
+                        let w = left.width().saturating_sub(pad as u32);
                         let h = q.height as u32 + pad as u32;
                         let x = left.x() + (pad/2);
                         let y = left.y() + (y_pos - pad/2);
                         self.selection.move_to(Rect::new(x, y, w, h));
                     }
+                    if let (Some(icon), Some((w, h))) = (&tab.icon, icon_size) {
+                        self.canvas.copy(icon, None, Rect::new(out_rect.x(), y_pos, w, h))
+                            .expect("Failed to draw icon of a tab");
+                        out_rect.offset(icon_width as i32, 0);
+                    }
                     self.canvas.copy(&tab.text, None, out_rect)
                         .expect("Failed to draw texture of a widget");
                     y_pos += q.height as i32;
                 }
                 y_pos += pad;
             }
+
+            let content_height = y_pos - self.tab_bar_scroll;
+            self.want_tab_bar_scroll = self.want_tab_bar_scroll.clamp((left.height() as i32 - content_height).min(0), 0);
+
+            // arrows hinting at tabs scrolled out of view
+            self.canvas.set_draw_color(Theme::fg_widgets());
+            let size = pad/2;
+            let cx = left.width() as i32 / 2;
+            for i in 0..size {
+                if self.tab_bar_scroll < 0 {
+                    self.canvas.draw_line((cx - i, i), (cx + i, i))
+                        .expect("Failed to draw tab bar scroll indicator");
+                }
+                if y_pos > left.height() as i32 {
+                    let y = left.height() as i32 - 1 - i;
+                    self.canvas.draw_line((cx - i, y), (cx + i, y))
+                        .expect("Failed to draw tab bar scroll indicator");
+                }
+            }
         }
 
         if let Some(right) = right_panel {
             self.canvas.set_draw_color(Theme::bg_widgets());
             self.canvas.fill_rect(right).expect("Failed to clear right side");
             self.canvas.set_viewport(right);
-            let offset = if let Some(left) = left_panel { left.width() as i32 } else { 0 };
+            let offset = right.x();
             let curtab = self.tabs.get_mut(self.current_tab).unwrap().as_mut()
                 .expect("current_tab should always be a valid index for a tab");
 
             let mut y_pos = right.y();
-            let pad = (Theme::padding() as i32 * self.font_height)  / 100;
             self.canvas.set_draw_color(Theme::fg_widgets());
+            let tile = self.font_height as u32 * Theme::tile_scale();
+            if curtab.layout == Layout::Grid {
+                curtab.columns = (right.width().saturating_sub(pad as u32) / (tile + pad as u32)).max(1) as usize;
+            }
+            self.widget_rows.clear();
+            self.page_height = right.height();
+            let mut slot = 0;
             for (i, widget) in curtab.widgets.iter_mut().enumerate() {
+                if !widget.flags.is_visible() {
+                    self.widget_rows.push(y_pos - right.y());
+                    continue;
+                }
+                if curtab.layout == Layout::Grid {
+                    let (row, col) = ((slot / curtab.columns) as i32, (slot % curtab.columns) as i32);
+                    slot += 1;
+                    let x = offset + pad + col * (tile as i32 + pad);
+                    let y = right.y() + pad + row * (tile as i32 + pad);
+                    self.widget_rows.push(y - right.y());
+
+                    let old_viewport = self.canvas.viewport();
+                    self.canvas.set_viewport(Rect::new(x, y, tile, tile));
+                    widget.draw_tile(&mut self.canvas);
+                    self.canvas.set_viewport(old_viewport);
+
+                    if i == self.current_widget && self.focus != Focus::TabBar {
+                        let selection_rect = Rect::new(x - pad/2, y - pad/2, tile + pad as u32, tile + pad as u32);
+                        if selection_rect.bottom() > top + right.height() as i32 {
+                            self.want_widget_scroll -= selection_rect.bottom() - top - right.height() as i32;
+                        }
+                        if selection_rect.top() < top {
+                            self.want_widget_scroll -= selection_rect.top() - top;
+                        }
+                        self.selection.move_to(selection_rect);
+                    }
+                    y_pos = y + tile as i32 + pad;
+                    continue;
+                }
                 y_pos += pad as i32;
+                // measured and drawn at the same width, inside the padding on both sides
+                let content_width = right.width().saturating_sub(2*pad as u32);
+                let height = widget.measure(&mut self.canvas, &self.font, &self.texture_creator, content_width);
+                self.widget_rows.push(y_pos - right.y());
 
                 let old_viewport = self.canvas.viewport();
-                let tmp_viewport = Rect::new(offset + pad, y_pos, right.width(), widget.height());
+                let tmp_viewport = Rect::new(offset + pad, y_pos, content_width, height);
 
                 self.canvas.set_viewport(tmp_viewport);
-                widget.draw(&mut self.canvas);
+                widget.draw(&mut self.canvas, &self.font, &self.texture_creator);
                 self.canvas.set_viewport(old_viewport);
 
                 if i == self.current_widget && self.focus != Focus::TabBar {
-                    let selection_rect = Rect::new(offset, y_pos - pad, right.width() - 1, widget.height() + 2*pad as u32);
+                    let selection_rect = Rect::new(offset, y_pos - pad, right.width() - 1, height + 2*pad as u32);
+                    if self.row_offset_of != (self.current_tab, i) {
+                        self.row_offset = 0;
+                        self.row_offset_of = (self.current_tab, i);
+                    }
+                    self.row_offset = self.row_offset.min(selection_rect.height().saturating_sub(right.height()));
+                    self.selected_height = selection_rect.height();
+                    let shown = shown_part(selection_rect, self.row_offset, right.height());
 
-                    if selection_rect.bottom() > right.height() as i32 {
-                        self.want_widget_scroll -= selection_rect.bottom() - right.height() as i32;
+                    if shown.bottom() > top + right.height() as i32 {
+                        self.want_widget_scroll -= shown.bottom() - top - right.height() as i32;
                     }
-                    if selection_rect.top() < 0 {
-                        self.want_widget_scroll -= selection_rect.top();
+                    if shown.top() < top {
+                        self.want_widget_scroll -= shown.top() - top;
                     }
 
                     self.selection.move_to(selection_rect);
                 }
 
-                y_pos += widget.height() as i32;
+                y_pos += height as i32;
                 y_pos += pad as i32;
             }
             self.canvas.set_draw_color(Theme::bg_widgets());
@@ -328,18 +870,100 @@ impl Gui {
             }
         }
 
+        if let Some(strip) = strip_panel {
+            self.canvas.set_viewport(None);
+            self.canvas.set_draw_color(Theme::bg_tabs());
+            self.canvas.fill_rect(strip).expect("Failed to clear tab bar");
+            self.canvas.set_viewport(strip);
+            let mut x_pos = self.tab_bar_scroll;
+            self.tab_starts.clear();
+            self.tab_page = strip.width();
+            for (i, t) in self.tabs.iter().enumerate() {
+                x_pos += pad;
+                self.tab_starts.push(x_pos - self.tab_bar_scroll);
+                if let Some(tab) = t {
+                    let q = tab.text.query();
+                    let icon_size = tab.icon.as_ref().map(|icon| image::scaled_to_height(icon, q.height));
+                    let icon_width = icon_size.map(|(w, _)| w + pad as u32).unwrap_or(0);
+                    if i == self.current_tab {
+                        let x = x_pos - pad/2;
+                        let w = (q.width + icon_width) as i32 + pad;
+                        if x + w > strip.width() as i32 {
+                            self.want_tab_bar_scroll -= x + w - strip.width() as i32;
+                        }
+                        if x < 0 {
+                            self.want_tab_bar_scroll -= x;
+                        }
+                        if self.focus == Focus::TabBar {
+                            self.selection.move_to(Rect::new(strip.x() + x, strip.y() + pad/2, w as u32, q.height + pad as u32));
+                        }
+                    }
+                    if let (Some(icon), Some((w, h))) = (&tab.icon, icon_size) {
+                        self.canvas.copy(icon, None, Rect::new(x_pos, pad, w, h))
+                            .expect("Failed to draw icon of a tab");
+                    }
+                    self.canvas.copy(&tab.text, None, Rect::new(x_pos + icon_width as i32, pad, q.width, q.height))
+                        .expect("Failed to draw texture of a widget");
+                    x_pos += (q.width + icon_width) as i32;
+                }
+                x_pos += pad;
+            }
+
+            let content_width = x_pos - self.tab_bar_scroll;
+            self.want_tab_bar_scroll = self.want_tab_bar_scroll.clamp((strip.width() as i32 - content_width).min(0), 0);
+
+            self.canvas.set_draw_color(Theme::fg_widgets());
+            let size = pad/2;
+            let cy = strip.height() as i32 / 2;
+            for i in 0..size {
+                if self.tab_bar_scroll < 0 {
+                    self.canvas.draw_line((i, cy - i), (i, cy + i))
+                        .expect("Failed to draw tab bar scroll indicator");
+                }
+                if x_pos > strip.width() as i32 {
+                    let x = strip.width() as i32 - 1 - i;
+                    self.canvas.draw_line((x, cy - i), (x, cy + i))
+                        .expect("Failed to draw tab bar scroll indicator");
+                }
+            }
+        }
+
         self.widget_scroll = closerize(self.widget_scroll, self.want_widget_scroll);
+        self.tab_bar_scroll = closerize(self.tab_bar_scroll, self.want_tab_bar_scroll);
     
         self.canvas.set_viewport(None);
+        if let Some(status_bar) = &mut self.status_bar {
+            status_bar.draw(&mut self.canvas, &self.font, &self.texture_creator, &mut self.images, width);
+        }
+        self.footer.draw(&mut self.canvas, (width, self.window_size.1.saturating_sub(hint_height)), self.font_height);
+        let hints = self.hints();
+        if let Some(hint_bar) = &mut self.hint_bar {
+            hint_bar.draw(&mut self.canvas, &hints, &self.font, &self.texture_creator, self.window_size);
+        }
         self.selection.tick();
         self.selection.draw(&mut self.canvas);
 
+        self.toasts.tick(&self.font, &self.texture_creator, self.window_size);
+        self.toasts.draw(&mut self.canvas, self.font_height);
+
         self.canvas.set_draw_color(Theme::bg_widgets());
         self.canvas.present();
-        None
+        event
     }
 }
 
+/// Where the tabs are listed, set with [`GuiBuilder::tab_bar_position`](crate::builders::GuiBuilder::tab_bar_position).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum TabBarPosition {
+    /// Left on wide windows, Top on short ones, and on portrait ones tabs and widgets take turns filling the window.
+    #[default]
+    Auto,
+    Left,
+    /// Horizontal strip, switched with Left/Right.
+    Top,
+    Bottom,
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub enum Focus {
     TabBar,
@@ -367,120 +991,305 @@ impl Focus {
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct Tab {
+    name: String,
     #[derivative(Debug="ignore")]
     text: Texture,
+    #[derivative(Debug="ignore")]
+    icon: Option<Rc<Texture>>,
     widgets: Vec<Widget>,
+    #[derivative(Debug="ignore")]
+    radio_groups: Vec<Box<dyn Fn(usize)>>,
+    // settings ID of every radio group, indexed like radio_groups
+    radio_ids: Vec<Option<String>>,
+    browser: Option<FileBrowser>,
+    layout: Layout,
+    // tiles per row as of the last drawn frame
+    columns: usize,
+    description: Option<String>,
+}
+
+/// How widgets of a tab are arranged in the right panel.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub(crate) enum Layout {
+    /// One widget per row.
+    #[default]
+    List,
+    /// Square tiles with an icon and a caption, as many per row as fit.
+    Grid,
+}
+
+/// Widget reached from `current` by `action` in a grid of `cols` columns, going over tiles that aren't `focusable`.
+/// `slots` are the widgets taking a tile, in order.
+fn grid_move(slots: &[usize], cols: usize, current: usize, action: ActionKey, focusable: impl Fn(usize) -> bool) -> usize {
+    let Some(mut slot) = slots.iter().position(|i| *i == current) else { return current };
+    let cols = cols.max(1);
+    let last = slots.len() - 1;
+    loop {
+        let next = match action {
+            ActionKey::Left if slot % cols != 0 => slot - 1,
+            ActionKey::Right if (slot + 1) % cols != 0 && slot < last => slot + 1,
+            ActionKey::Up if slot >= cols => slot - cols,
+            // the last row might not be full
+            ActionKey::Down if slot / cols < last / cols => (slot + cols).min(last),
+            _ => slot,
+        };
+        if next == slot {
+            return current;
+        }
+        slot = next;
+        if focusable(slots[slot]) {
+            return slots[slot];
+        }
+    }
+}
+
+/// Item about a `page` away from `current` in a list of `count` items starting at `starts` as drawn last,
+/// going over the ones that aren't `wanted`. A page further than the next item still moves to it.
+fn page_move(starts: &[i32], count: usize, page: u32, current: usize, down: bool, wanted: impl Fn(usize) -> bool) -> Option<usize> {
+    let from = starts.get(current).copied().unwrap_or(0);
+    if down {
+        (current + 1..count).rev()
+            .filter(|i| wanted(*i))
+            .find(|i| starts.get(*i).is_some_and(|start| *start <= from + page as i32))
+            .or_else(|| (current + 1..count).find(|i| wanted(*i)))
+    } else {
+        (0..current)
+            .filter(|i| wanted(*i))
+            .find(|i| starts.get(*i).is_some_and(|start| *start >= from - page as i32))
+            .or_else(|| (0..current).rev().find(|i| wanted(*i)))
+    }
+}
+
+/// Offset into a row of `height` taller than the `page` after moving by `step` towards its bottom or top.
+/// None once that end is shown, so the selection moves on to the next widget.
+fn scroll_within(offset: u32, height: u32, page: u32, step: u32, down: bool) -> Option<u32> {
+    let end = height.saturating_sub(page);
+    match down {
+        true if offset < end => Some((offset + step).min(end)),
+        false if offset > 0 => Some(offset.saturating_sub(step)),
+        _ => None,
+    }
+}
+
+/// Part of the selected `row` that has to be on screen: all of it if it fits the `page`, else a page from `offset`.
+fn shown_part(row: Rect, offset: u32, page: u32) -> Rect {
+    if row.height() <= page {
+        return row;
+    }
+    let offset = offset.min(row.height() - page);
+    Rect::new(row.x(), row.y() + offset as i32, row.width(), page)
+}
+
+impl Tab {
+    fn grid_move(&self, current: usize, action: ActionKey) -> usize {
+        // hidden tiles take no slot
+        let slots = self.widgets.iter()
+            .enumerate()
+            .filter(|(_, w)| w.flags.is_visible())
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        grid_move(&slots, self.columns, current, action, |i| self.widgets[i].focusable())
+    }
+    /// Regenerates rows of tabs with dynamic content, returning them with the row to select.
+    fn refresh(&mut self, font: &Font, texture_creator: &TextureCreator<WindowContext>, images: &mut ImageCache) -> Option<(Vec<Widget>, usize)> {
+        self.browser.as_mut()?.refresh(font, texture_creator, images)
+    }
+    /// Turns on the radio button at `index` and the others of its group off, running the group callback
+    /// unless it already was on.
+    fn select_radio(&mut self, index: usize) {
+        let Some(group) = self.widgets[index].group else { return };
+        if matches!(self.widgets[index].control.state(), Some(WidgetState::Radio(true, _))) {
+            return;
+        }
+        let mut selected = 0;
+        for (i, widget) in self.widgets.iter_mut()
+            .enumerate()
+            .filter(|(_, w)| w.group == Some(group))
+        {
+            if let Some(WidgetState::Radio(ref mut state, _)) = widget.control.state() {
+                *state = i == index;
+            }
+            if i < index {
+                selected += 1;
+            }
+        }
+        (self.radio_groups[group])(selected);
+    }
+    /// Index of the option that is on in every radio group with an ID, keyed by that ID.
+    fn radio_values(&mut self) -> Vec<(String, String)> {
+        let mut values = Vec::new();
+        for (group, id) in self.radio_ids.iter().enumerate() {
+            let Some(id) = id else { continue };
+            let selected = self.widgets.iter_mut()
+                .filter(|w| w.group == Some(group))
+                .position(|w| matches!(w.control.state(), Some(WidgetState::Radio(true, _))));
+            if let Some(selected) = selected {
+                values.push((id.clone(), selected.to_string()));
+            }
+        }
+        values
+    }
+    /// Selects the saved option of radio groups with an ID, ignoring indices past the end of the group.
+    fn restore_radios(&mut self, settings: &Settings) {
+        let restored = self.radio_ids.iter()
+            .enumerate()
+            .filter_map(|(group, id)| Some((group, settings.get(id.as_deref()?)?.parse::<usize>().ok()?)))
+            .filter_map(|(group, option)| self.widgets.iter()
+                .enumerate()
+                .filter(|(_, w)| w.group == Some(group))
+                .nth(option)
+                .map(|(i, _)| i))
+            .collect::<Vec<_>>();
+        for index in restored {
+            self.select_radio(index);
+        }
+    }
 }
 
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct Widget {
+    name: String,
+    // None for widgets without a label
     #[derivative(Debug="ignore")]
-    text: Texture,
-    state: WidgetState,
+    text: Option<Texture>,
+    #[derivative(Debug="ignore")]
+    control: Box<dyn Control>,
+    // index into Tab::radio_groups
+    group: Option<usize>,
     #[derivative(Debug="ignore")]
-    callback: Option<Box<dyn Fn(&mut WidgetState, &Cell<Instant>)>>,
-    next_callback: Cell<Instant>,
+    icon: Option<Rc<Texture>>,
+    flags: WidgetFlags,
+    description: Option<String>,
+    // key in Settings
+    id: Option<String>,
 }
 
-impl Widget {
-    fn height(&self) -> u32 {
-        let query = self.text.query();
-        query.height
-    }
-    fn draw(&mut self, canvas: &mut render::Canvas<video::Window>) {
-        let query = self.text.query();
-        let text_rect = Rect::new(0, 0, query.width, query.height);
-        canvas.copy(&self.text, None, text_rect).expect("Failed to draw a widget");
-        
-        let bounds = canvas.viewport();
-        let margin = bounds.height()/4;
-        let box_size = bounds.height() - margin*2;
-        match self.state {
-            WidgetState::Toggle(state, ref mut opacity) => {
-                let textbox_rect = Rect::new((bounds.width() - (margin*2 + box_size)) as i32, margin as i32, box_size, box_size);
-                if state {
-                    *opacity = closerize(*opacity as i32, 255) as u8;
-                } else {
-                    *opacity = closerize(*opacity as i32, 0) as u8;
-                }
-                let old = canvas.draw_color();
-                canvas.set_draw_color((old.r, old.g, old.b, *opacity));
-                canvas.fill_rect(textbox_rect).expect("Failed to draw toggle widget");
-                canvas.set_draw_color((old.r, old.g, old.b));
-                canvas.draw_rect(textbox_rect).expect("Failed to draw toggle widget");
-            },
-            WidgetState::Slider(state, ref mut display_state) => {
-                if *display_state != state {
-                    *display_state = closerize(*display_state as i32, state as i32) as u8;
-                }
-                // try not overlapping text
-                let whole_width = if query.width > bounds.width()/2 {
-                    bounds.width() - query.width - margin*4
-                } else {
-                    bounds.width()/2 - margin*4
-                };
-                let x_pos = bounds.width() - whole_width - margin*2;
-                let state_width = *display_state as f32 / u8::MAX as f32 * whole_width as f32;
+/// Cloneable handle for disabling or hiding a widget while the Gui is running, also from other threads.
+#[derive(Debug, Clone)]
+pub struct WidgetFlags {
+    enabled: Arc<AtomicBool>,
+    visible: Arc<AtomicBool>,
+}
+
+impl Default for WidgetFlags {
+    fn default() -> Self {
+        Self {
+            enabled: Arc::new(AtomicBool::new(true)),
+            visible: Arc::new(AtomicBool::new(true)),
+        }
+    }
+}
 
-                let rect = Rect::new(x_pos as i32, margin as i32, whole_width, box_size);
-                let state_rect = Rect::new(x_pos as i32, margin as i32, state_width as u32, box_size);
+impl WidgetFlags {
+    /// Disabled widgets are dimmed, skipped when navigating and ignore all actions.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+    /// Hidden widgets take no space.
+    pub fn set_visible(&self, visible: bool) {
+        self.visible.store(visible, Ordering::Relaxed);
+    }
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+    pub fn is_visible(&self) -> bool {
+        self.visible.load(Ordering::Relaxed)
+    }
+}
 
-                canvas.draw_rect(rect).expect("Failed to draw slider widget");
-                canvas.fill_rect(state_rect).expect("Failed to draw slider widget");
+impl Widget {
+    /// Area taken by the icon and the label, which is what all kinds of widgets have in common.
+    fn label_rect(&self) -> Rect {
+        let (text_w, text_h) = self.text.as_ref()
+            .map(|t| (t.query().width, t.query().height))
+            .unwrap_or((0, 0));
+        match &self.icon {
+            Some(icon) => {
+                let (w, h) = image::scaled_to_height(icon, text_h);
+                Rect::new(0, 0, w + text_h/4 + text_w, h.max(text_h))
             },
-            WidgetState::Button => (),
+            None => Rect::new(0, 0, text_w, text_h),
         }
-        let use_timer = match self.state {
-            WidgetState::Button => false,
-            WidgetState::Slider(..) => true,
-            WidgetState::Toggle(..) => true,
-        };
-        if use_timer && Instant::now() > self.next_callback.get() {
-            if let Some(cb) = &self.callback {
-                self.next_callback.set(Instant::now() + Duration::from_secs(0xFFFFFFFFF));
-                cb(&mut self.state, &self.next_callback);
-            }
+    }
+    /// Returns a failure the control wants shown.
+    fn poll(&mut self) -> Option<String> {
+        self.control.poll();
+        self.control.take_error()
+    }
+    /// Frees the label, icons are shared through ImageCache and stay alive.
+    fn destroy(self) {
+        if let Some(text) = self.text {
+            destroy_texture(text);
         }
     }
-    fn grabs_input(&self) -> bool {
-        match self.state {
-            WidgetState::Button | WidgetState::Toggle(..) => false,
-            WidgetState::Slider(..) => true,
+    fn measure(&mut self, canvas: &mut render::Canvas<video::Window>, font: &Font<'static, 'static>, texture_creator: &TextureCreator<WindowContext>, width: u32) -> u32 {
+        let label = self.label_rect();
+        let mut ctx = Context { canvas, font, texture_creator, width, label };
+        self.control.measure(&mut ctx).max(label.height())
+    }
+    fn draw_tile(&mut self, canvas: &mut render::Canvas<video::Window>) {
+        let bounds = canvas.viewport();
+        canvas.draw_rect(Rect::new(0, 0, bounds.width(), bounds.height())).expect("Failed to draw a tile");
+        let text_height = self.text.as_ref().map(|t| t.query().height).unwrap_or(0);
+        let icon_area = bounds.height().saturating_sub(text_height);
+        if let Some(icon) = &self.icon {
+            let (w, h) = image::scaled_to_fit(icon, bounds.width() * 3/4, icon_area * 3/4);
+            let x = (bounds.width() - w) as i32 / 2;
+            let y = (icon_area - h) as i32 / 2;
+            canvas.copy(icon, None, Rect::new(x, y, w, h)).expect("Failed to draw icon of a tile");
+        }
+        if let Some(text) = &self.text {
+            // long captions get cut off on both sides
+            let q = text.query();
+            let w = q.width.min(bounds.width());
+            let src = Rect::new((q.width - w) as i32 / 2, 0, w, q.height);
+            let dst = Rect::new((bounds.width() - w) as i32 / 2, icon_area as i32, w, q.height);
+            canvas.copy(text, src, dst).expect("Failed to draw caption of a tile");
         }
+        self.dim_if_disabled(canvas);
     }
-    fn process_action(&mut self, code: &ActionKey) {
-        let mut fire_callback = false;
-        match self.state {
-            WidgetState::Button => {
-                if *code == ActionKey::Press {
-                    fire_callback = true;
-                }
-            }
-            WidgetState::Toggle(ref mut state, _) => {
-                if *code == ActionKey::Press {
-                    *state = !*state;
-                    fire_callback = true;
-                }
-            },
-            WidgetState::Slider(ref mut state, ..) => {
-                match code {
-                    ActionKey::Left => {
-                        *state = state.saturating_sub(12);
-                        fire_callback = true;
-                    },
-                    ActionKey::Right => {
-                        *state = state.saturating_add(12);
-                        fire_callback = true;
-                    },
-                    _ => (),
-                }
-            }
+    fn draw(&mut self, canvas: &mut render::Canvas<video::Window>, font: &Font<'static, 'static>, texture_creator: &TextureCreator<WindowContext>) {
+        let label = self.label_rect();
+        let mut text_x = 0;
+        if let Some(icon) = &self.icon {
+            let text_height = self.text.as_ref().map(|t| t.query().height).unwrap_or(0);
+            let (w, h) = image::scaled_to_height(icon, text_height);
+            canvas.copy(icon, None, Rect::new(0, 0, w, h)).expect("Failed to draw icon of a widget");
+            text_x = (w + text_height/4) as i32;
         }
-        if fire_callback {
-            if let Some(cb) = &self.callback {
-                cb(&mut self.state, &self.next_callback);
-            }
+        if let Some(text) = &self.text {
+            let query = text.query();
+            canvas.copy(text, None, Rect::new(text_x, 0, query.width, query.height)).expect("Failed to draw a widget");
+        }
+
+        let width = canvas.viewport().width();
+        let mut ctx = Context { canvas, font, texture_creator, width, label };
+        self.control.draw(&mut ctx);
+        self.dim_if_disabled(canvas);
+    }
+    /// Fades out whatever was drawn in the viewport.
+    fn dim_if_disabled(&self, canvas: &mut render::Canvas<video::Window>) {
+        if self.flags.is_enabled() {
+            return;
+        }
+        let old = canvas.draw_color();
+        let (r, g, b) = Theme::bg_widgets();
+        let bounds = canvas.viewport();
+        canvas.set_draw_color((r, g, b, Theme::disabled_fade()));
+        canvas.fill_rect(Rect::new(0, 0, bounds.width(), bounds.height())).expect("Failed to draw a disabled widget");
+        canvas.set_draw_color(old);
+    }
+    /// Whether the selection can land on the widget right now.
+    fn focusable(&self) -> bool {
+        self.flags.is_visible() && self.flags.is_enabled() && self.control.focusable()
+    }
+    fn grabs_input(&self) -> bool {
+        self.flags.is_enabled() && self.control.grabs_input()
+    }
+    fn process_action(&mut self, code: &ActionKey) {
+        if self.flags.is_enabled() {
+            self.control.process_action(code);
         }
     }
     #[allow(dead_code, unused_variables)]
@@ -489,9 +1298,107 @@ impl Widget {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum WidgetState {
     Button,
     Toggle(bool, u8),
     Slider(u8, u8),
+    Radio(bool, u8),
+    Color(u8, u8, u8),
+    DateTime(DateTime),
+    Binding(Option<Binding>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3 columns, tiles 0..7:
+    // 0 1 2
+    // 3 4 5
+    // 6
+    const SLOTS: [usize; 7] = [0, 1, 2, 3, 4, 5, 6];
+
+    fn step(current: usize, action: ActionKey) -> usize {
+        grid_move(&SLOTS, 3, current, action, |_| true)
+    }
+
+    #[test]
+    fn grid_moves_within_rows_and_columns() {
+        assert_eq!(step(4, ActionKey::Left), 3);
+        assert_eq!(step(4, ActionKey::Right), 5);
+        assert_eq!(step(4, ActionKey::Up), 1);
+        assert_eq!(step(1, ActionKey::Down), 4);
+    }
+
+    #[test]
+    fn grid_stops_at_edges() {
+        assert_eq!(step(3, ActionKey::Left), 3);
+        assert_eq!(step(2, ActionKey::Right), 2);
+        assert_eq!(step(6, ActionKey::Right), 6);
+        assert_eq!(step(1, ActionKey::Up), 1);
+        assert_eq!(step(6, ActionKey::Down), 6);
+    }
+
+    #[test]
+    fn grid_down_into_a_short_last_row() {
+        assert_eq!(step(5, ActionKey::Down), 6);
+    }
+
+    #[test]
+    fn grid_skips_unfocusable_and_hidden_tiles() {
+        assert_eq!(grid_move(&SLOTS, 3, 3, ActionKey::Right, |i| i != 4), 5);
+        assert_eq!(grid_move(&SLOTS, 3, 3, ActionKey::Right, |i| i == 3), 3);
+        // widget 1 is hidden, so 2 takes its tile
+        assert_eq!(grid_move(&[0, 2, 3, 4], 3, 3, ActionKey::Left, |_| true), 2);
+        assert_eq!(grid_move(&[0, 2, 3, 4], 3, 4, ActionKey::Up, |_| true), 0);
+    }
+
+    // items 30 apart, a page of 100
+    const STARTS: [i32; 10] = [0, 30, 60, 90, 120, 150, 180, 210, 240, 270];
+
+    #[test]
+    fn pages_by_position() {
+        assert_eq!(page_move(&STARTS, 10, 100, 0, true, |_| true), Some(3));
+        assert_eq!(page_move(&STARTS, 10, 100, 8, false, |_| true), Some(5));
+        assert_eq!(page_move(&STARTS, 10, 100, 9, true, |_| true), None);
+        assert_eq!(page_move(&STARTS, 10, 100, 0, false, |_| true), None);
+    }
+
+    #[test]
+    fn pages_over_unwanted_items() {
+        assert_eq!(page_move(&STARTS, 10, 100, 0, true, |i| i != 3), Some(2));
+        // nothing within a page still moves to the next one
+        assert_eq!(page_move(&STARTS, 10, 100, 0, true, |i| i == 0 || i > 6), Some(7));
+        assert_eq!(page_move(&STARTS, 10, 100, 9, false, |i| i < 2 || i == 9), Some(1));
+        // items not drawn yet are only reached one at a time
+        assert_eq!(page_move(&STARTS[..2], 10, 100, 1, true, |_| true), Some(2));
+    }
+
+    #[test]
+    fn scrolls_through_a_paragraph_taller_than_the_panel() {
+        // 250 high in a 100 high panel, half a page per step
+        let mut offsets = vec![0];
+        while let Some(next) = scroll_within(*offsets.last().unwrap(), 250, 100, 50, true) {
+            offsets.push(next);
+        }
+        assert_eq!(offsets, [0, 50, 100, 150]);
+        assert_eq!(scroll_within(150, 250, 100, 50, false), Some(100));
+        assert_eq!(scroll_within(20, 250, 100, 50, false), Some(0));
+        assert_eq!(scroll_within(0, 250, 100, 50, false), None);
+
+        let row = Rect::new(0, 300, 10, 250);
+        assert_eq!(shown_part(row, 0, 100), Rect::new(0, 300, 10, 100));
+        assert_eq!(shown_part(row, 150, 100), Rect::new(0, 450, 10, 100));
+        // entering from below starts at the bottom
+        assert_eq!(shown_part(row, u32::MAX, 100).bottom(), row.bottom());
+    }
+
+    #[test]
+    fn rows_that_fit_are_not_scrolled_through() {
+        assert_eq!(scroll_within(0, 80, 100, 50, true), None);
+        assert_eq!(scroll_within(0, 80, 100, 50, false), None);
+        let row = Rect::new(0, 300, 10, 80);
+        assert_eq!(shown_part(row, 30, 100), row);
+    }
 }
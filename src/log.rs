@@ -0,0 +1,280 @@
+use crate::{ActionKey, destroy_texture, theme::Theme, control::{Context, Control}};
+use sdl2::{
+    render::{Texture, TextureCreator},
+    video::WindowContext,
+    rect::Rect,
+    ttf::Font,
+};
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogLine {
+    pub text: String,
+    pub stream: LogStream,
+}
+
+impl LogLine {
+    pub fn stdout(text: impl ToString) -> Self {
+        Self { text: text.to_string(), stream: LogStream::Stdout }
+    }
+    pub fn stderr(text: impl ToString) -> Self {
+        Self { text: text.to_string(), stream: LogStream::Stderr }
+    }
+}
+
+// Longest part of a line that is rendered, the rest never fits the widget anyway.
+const MAX_CHARS: usize = 1024;
+
+fn forward(reader: impl Read + Send + 'static, stream: LogStream, tx: Sender<LogLine>) {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut bytes = Vec::new();
+        // invalid UTF-8 is replaced rather than ending the log
+        while matches!(reader.read_until(b'\n', &mut bytes), Ok(n) if n > 0) {
+            let text = String::from_utf8_lossy(&bytes);
+            let text = text.trim_end_matches(['\n', '\r']).to_string();
+            bytes.clear();
+            if tx.send(LogLine { text, stream }).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+fn render(text: &str, color: (u8, u8, u8), font: &Font, texture_creator: &TextureCreator<WindowContext>) -> Option<Texture> {
+    let surface = font.render(text).blended(color).ok()?;
+    texture_creator.create_texture_from_surface(&surface).ok()
+}
+
+/// Spawns `cmd` with piped stdout and stderr, forwarding every line into the returned channel.
+pub(crate) fn spawn(cmd: &mut Command) -> io::Result<(Receiver<LogLine>, Child)> {
+    let mut child = cmd.stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let (tx, rx) = mpsc::channel();
+    if let Some(stdout) = child.stdout.take() {
+        forward(stdout, LogStream::Stdout, tx.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward(stderr, LogStream::Stderr, tx);
+    }
+    Ok((rx, child))
+}
+
+pub(crate) struct LogView {
+    rx: Receiver<LogLine>,
+    capacity: usize,
+    lines: VecDeque<(LogLine, Option<Texture>)>,
+    // lines between the bottom of the view and the newest line, 0 follows the tail
+    scroll: usize,
+    line_height: u32,
+    // process feeding the log, killed along with the widget
+    child: Option<Child>,
+}
+
+impl LogView {
+    pub(crate) fn new(rx: Receiver<LogLine>, capacity: usize) -> Self {
+        Self {
+            rx,
+            capacity: capacity.max(1),
+            lines: VecDeque::new(),
+            scroll: 0,
+            line_height: 0,
+            child: None,
+        }
+    }
+    pub(crate) fn with_child(mut self, child: Child) -> Self {
+        self.child = Some(child);
+        self
+    }
+    fn max_scroll(&self) -> usize {
+        self.lines.len().saturating_sub(Theme::log_rows())
+    }
+    fn visible(&self) -> std::ops::Range<usize> {
+        let end = self.lines.len() - self.scroll;
+        end.saturating_sub(Theme::log_rows())..end
+    }
+    fn layout(&mut self, font: &Font, texture_creator: &TextureCreator<WindowContext>) {
+        self.line_height = font.height() as u32;
+        for i in self.visible() {
+            let (line, texture) = &mut self.lines[i];
+            if texture.is_none() {
+                let color = match line.stream {
+                    LogStream::Stdout => Theme::fg_widgets(),
+                    LogStream::Stderr => Theme::log_stderr(),
+                };
+                // SDL_ttf refuses empty text and NUL bytes
+                let text = line.text.chars()
+                    .take(MAX_CHARS)
+                    .map(|c| if c == '\0' { ' ' } else { c })
+                    .collect::<String>();
+                let text = if text.is_empty() { " " } else { &text };
+                // a line that still can't be rendered is left blank
+                *texture = render(text, color, font, texture_creator)
+                    .or_else(|| render(" ", color, font, texture_creator));
+            }
+        }
+    }
+}
+
+impl Control for LogView {
+    fn poll(&mut self) {
+        for line in self.rx.try_iter() {
+            if self.lines.len() == self.capacity {
+                if let Some((_, Some(texture))) = self.lines.pop_front() {
+                    destroy_texture(texture);
+                }
+            }
+            self.lines.push_back((line, None));
+            if self.scroll > 0 {
+                // keep the view still while the user is reading older output
+                self.scroll += 1;
+            }
+        }
+        self.scroll = self.scroll.min(self.max_scroll());
+        // reap the child once it exits so it doesn't linger as a zombie
+        if let Some(child) = &mut self.child {
+            if !matches!(child.try_wait(), Ok(None)) {
+                self.child = None;
+            }
+        }
+    }
+    fn measure(&mut self, ctx: &mut Context) -> u32 {
+        self.layout(ctx.font, ctx.texture_creator);
+        ctx.label.height() + Theme::log_rows() as u32 * self.line_height
+    }
+    fn draw(&mut self, ctx: &mut Context) {
+        let bounds = ctx.canvas.viewport();
+        let label_height = ctx.label.height();
+        let area = Rect::new(0, label_height as i32, bounds.width() - label_height, bounds.height() - label_height);
+        ctx.canvas.draw_rect(area).expect("Failed to draw log widget");
+        let mut y = area.y();
+        for (_, texture) in self.visible().map(|i| &self.lines[i]) {
+            if let Some(texture) = texture {
+                let q = texture.query();
+                let width = q.width.min(area.width().saturating_sub(4));
+                ctx.canvas.copy(texture, Rect::new(0, 0, width, q.height), Rect::new(area.x() + 2, y, width, q.height))
+                    .expect("Failed to draw log widget");
+            }
+            y += self.line_height as i32;
+        }
+    }
+    fn process_action(&mut self, action: &ActionKey) {
+        match action {
+            ActionKey::Up => self.scroll = (self.scroll + 1).min(self.max_scroll()),
+            ActionKey::Down => self.scroll = self.scroll.saturating_sub(1),
+            ActionKey::PageUp => self.scroll = (self.scroll + Theme::log_rows()).min(self.max_scroll()),
+            ActionKey::PageDown => self.scroll = self.scroll.saturating_sub(Theme::log_rows()),
+            ActionKey::Home => self.scroll = self.max_scroll(),
+            ActionKey::End => self.scroll = 0,
+            _ => (),
+        }
+    }
+    fn grabs_input(&self) -> bool {
+        true
+    }
+    fn hints(&self, back: &'static str) -> Vec<(&'static str, &'static str)> {
+        vec![("\u{2191}\u{2193}", "Scroll"), (back, "Done")]
+    }
+}
+
+impl Drop for LogView {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.child {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(capacity: usize) -> (Sender<LogLine>, LogView) {
+        let (tx, rx) = mpsc::channel();
+        (tx, LogView::new(rx, capacity))
+    }
+
+    fn texts(view: &LogView) -> Vec<&str> {
+        view.lines.iter().map(|(line, _)| line.text.as_str()).collect()
+    }
+
+    #[test]
+    fn forwards_lines_with_invalid_utf8() {
+        let (tx, rx) = mpsc::channel();
+        forward(&b"one\r\ntwo \xff\n\nthree"[..], LogStream::Stderr, tx);
+        let lines = rx.iter().map(|line| line.text).collect::<Vec<_>>();
+        assert_eq!(lines, ["one", "two \u{fffd}", "", "three"]);
+    }
+
+    #[test]
+    fn keeps_only_the_newest_lines() {
+        let (tx, mut view) = view(3);
+        for i in 0..5 {
+            tx.send(LogLine::stdout(i)).unwrap();
+        }
+        view.poll();
+        assert_eq!(texts(&view), ["2", "3", "4"]);
+        tx.send(LogLine::stdout(5)).unwrap();
+        view.poll();
+        assert_eq!(texts(&view), ["3", "4", "5"]);
+    }
+
+    #[test]
+    fn follows_the_tail_unless_scrolled_back() {
+        let rows = Theme::log_rows();
+        let (tx, mut view) = view(100);
+        for i in 0..rows + 2 {
+            tx.send(LogLine::stdout(i)).unwrap();
+        }
+        view.poll();
+        assert_eq!(view.max_scroll(), 2);
+        assert_eq!(view.visible(), 2..rows + 2);
+
+        // scrolling stops at the oldest line
+        view.process_action(&ActionKey::Home);
+        assert_eq!(view.scroll, 2);
+        view.process_action(&ActionKey::Up);
+        assert_eq!(view.scroll, 2);
+
+        // new output doesn't move a view that was scrolled back
+        tx.send(LogLine::stdout("new")).unwrap();
+        view.poll();
+        assert_eq!(view.scroll, 3);
+        assert_eq!(view.visible(), 0..rows);
+
+        // at the bottom it follows again
+        view.process_action(&ActionKey::End);
+        tx.send(LogLine::stdout("newer")).unwrap();
+        view.poll();
+        assert_eq!(view.scroll, 0);
+        assert_eq!(view.visible(), 4..rows + 4);
+    }
+
+    #[test]
+    fn scrolled_back_view_is_clamped_when_old_lines_go() {
+        let rows = Theme::log_rows();
+        let (tx, mut view) = view(rows + 1);
+        for i in 0..rows + 1 {
+            tx.send(LogLine::stdout(i)).unwrap();
+        }
+        view.poll();
+        view.process_action(&ActionKey::Home);
+        assert_eq!(view.scroll, 1);
+        tx.send(LogLine::stdout("new")).unwrap();
+        view.poll();
+        assert_eq!(view.scroll, view.max_scroll());
+        assert_eq!(view.visible(), 0..rows);
+    }
+}
@@ -0,0 +1,208 @@
+//! Menus and dialogs for shell scripts, in the spirit of `dialog` and `whiptail`.
+//!
+//! The result goes to stdout, the exit code tells how the dialog ended:
+//! 0 when confirmed, 1 when cancelled, 2 for bad arguments or definitions and 255 when the window was closed.
+
+use sgui2::builders::{GuiBuilder, TabBuilder, WidgetData};
+use sgui2::{Callbacks, DefinitionError, Format, Gui, GuiEvent};
+use std::cell::Cell;
+use std::io::{self, Read};
+use std::process;
+use std::rc::Rc;
+
+const USAGE: &str = "\
+Usage: sgui2 [--title TITLE] DIALOG
+
+Dialogs:
+  --menu TEXT [TAG ITEM]...              prints the tag of the chosen item
+  --checklist TEXT [TAG ITEM on|off]...  prints the tags of checked items, one per line
+  --radiolist TEXT [TAG ITEM on|off]...  prints the tag of the selected item, starting with the
+                                         first one on; with none on, OK prints nothing until one is picked
+  --yesno TEXT                           exits with 0 for yes and 1 for no
+  --msgbox TEXT
+  --definition FILE [--json]             TOML or JSON definition, `-` reads stdin;
+                                         prints id=value of widgets with an ID.
+                                         Buttons with the callback \"ok\" or \"cancel\" end the dialog.
+
+Menus and lists need at least one item.
+
+Exit codes: 0 ok, 1 cancel, 2 usage or definition error, 255 window closed";
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Outcome {
+    Ok = 0,
+    Cancel = 1,
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("sgui2: {message}\n\n{USAGE}");
+    process::exit(2);
+}
+
+/// Button ending the dialog with `outcome`.
+fn finish_button(label: &str, finished: &Rc<Cell<Option<Outcome>>>, outcome: Outcome) -> WidgetData {
+    let finished = finished.clone();
+    WidgetData::btn(label, move |_, _| finished.set(Some(outcome)))
+}
+
+/// Splits `args` into rows of `n` items, at least one.
+fn rows(args: &[String], n: usize) -> Vec<&[String]> {
+    if args.is_empty() {
+        usage_error("expected at least one item");
+    }
+    if args.len() % n != 0 {
+        usage_error(&format!("expected items in groups of {n}"));
+    }
+    args.chunks(n).collect()
+}
+
+fn checked(status: &str) -> bool {
+    match status.to_lowercase().as_str() {
+        "on" | "yes" | "1" | "true" => true,
+        "off" | "no" | "0" | "false" => false,
+        _ => usage_error(&format!("item status should be on or off, not \"{status}\"")),
+    }
+}
+
+fn label(tag: &str, item: &str) -> String {
+    if item.is_empty() { tag.to_string() } else { item.to_string() }
+}
+
+/// Runs the Gui until a button sets `finished` or the window is closed.
+fn run(gui: &mut Gui, finished: &Cell<Option<Outcome>>) -> Outcome {
+    loop {
+        if gui.tick() == Some(GuiEvent::Quit) {
+            process::exit(255);
+        }
+        if let Some(outcome) = finished.get() {
+            return outcome;
+        }
+    }
+}
+
+fn read_definition(path: &str, json: bool, finished: &Rc<Cell<Option<Outcome>>>) -> Result<GuiBuilder, DefinitionError> {
+    let (ok, cancel) = (finished.clone(), finished.clone());
+    let mut callbacks = Callbacks::new();
+    callbacks
+        .add("ok", move |_, _| ok.set(Some(Outcome::Ok)))
+        .add("cancel", move |_, _| cancel.set(Some(Outcome::Cancel)));
+
+    if path != "-" {
+        return GuiBuilder::from_file(path, &callbacks);
+    }
+    let mut source = String::new();
+    io::stdin().read_to_string(&mut source).map_err(|e| DefinitionError {
+        line: None,
+        column: None,
+        message: format!("Failed to read stdin: {e}"),
+    })?;
+    GuiBuilder::from_str(&source, if json { Format::Json } else { Format::Toml }, &callbacks)
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let mut title = "sgui2".to_string();
+    if args.first().map(String::as_str) == Some("--title") {
+        if args.len() < 2 {
+            usage_error("--title needs a value");
+        }
+        title = args.remove(1);
+        args.remove(0);
+    }
+    let Some((dialog, rest)) = args.split_first() else { usage_error("no dialog given") };
+    if dialog == "--help" || dialog == "-h" {
+        println!("{USAGE}");
+        return;
+    }
+
+    let finished = Rc::new(Cell::new(None));
+    if dialog == "--definition" {
+        let (path, json) = match rest {
+            [path] => (path, false),
+            [path, flag] if flag == "--json" => (path, true),
+            _ => usage_error("--definition takes a file and optionally --json"),
+        };
+        let mut gui = match read_definition(path, json, &finished) {
+            Ok(mut builder) => builder.build(),
+            Err(e) => {
+                eprintln!("sgui2: {path}: {e}");
+                process::exit(2);
+            },
+        };
+        let outcome = run(&mut gui, &finished);
+        if outcome == Outcome::Ok {
+            for (id, value) in gui.values() {
+                println!("{id}={value}");
+            }
+        }
+        process::exit(outcome as i32);
+    }
+
+    let Some((text, items)) = rest.split_first() else { usage_error(&format!("{dialog} needs a text")) };
+    let mut builder = GuiBuilder::new(&title);
+    let mut tab = TabBuilder::new(&title);
+    tab.widget(WidgetData::paragraph(text));
+    // prints the result once the dialog was confirmed
+    let report: Box<dyn Fn(&mut Gui)> = match dialog.as_str() {
+        "--menu" => {
+            let chosen = Rc::new(Cell::new(None));
+            for (i, row) in rows(items, 2).into_iter().enumerate() {
+                let (chosen, finished) = (chosen.clone(), finished.clone());
+                tab.widget(WidgetData::btn(label(&row[0], &row[1]), move |_, _| {
+                    chosen.set(Some(i));
+                    finished.set(Some(Outcome::Ok));
+                }));
+            }
+            tab.widget(finish_button("Cancel", &finished, Outcome::Cancel));
+            let tags = rows(items, 2).into_iter().map(|row| row[0].clone()).collect::<Vec<_>>();
+            Box::new(move |_| if let Some(i) = chosen.get() {
+                println!("{}", tags[i]);
+            })
+        },
+        "--checklist" => {
+            for row in rows(items, 3) {
+                // tags double as IDs, so the values come back from Gui::values
+                tab.widget(WidgetData::toggle(label(&row[0], &row[1]), |_, _| {}, checked(&row[2])).with_id(&row[0]));
+            }
+            tab.widget(finish_button("OK", &finished, Outcome::Ok));
+            tab.widget(finish_button("Cancel", &finished, Outcome::Cancel));
+            Box::new(|gui| for (tag, value) in gui.values() {
+                if value == "true" {
+                    println!("{tag}");
+                }
+            })
+        },
+        "--radiolist" => {
+            let rows = rows(items, 3);
+            // with no item on nothing is selected until one is picked, and OK prints nothing
+            let initial = rows.iter().position(|row| checked(&row[2]));
+            let selected = Rc::new(Cell::new(initial));
+            let on_select = selected.clone();
+            tab.radio_group(rows.iter().map(|row| label(&row[0], &row[1])), initial.unwrap_or(rows.len()), move |i| on_select.set(Some(i)));
+            tab.widget(finish_button("OK", &finished, Outcome::Ok));
+            tab.widget(finish_button("Cancel", &finished, Outcome::Cancel));
+            let tags = rows.iter().map(|row| row[0].clone()).collect::<Vec<_>>();
+            Box::new(move |_| if let Some(i) = selected.get() {
+                println!("{}", tags[i]);
+            })
+        },
+        "--yesno" if items.is_empty() => {
+            tab.widget(finish_button("Yes", &finished, Outcome::Ok));
+            tab.widget(finish_button("No", &finished, Outcome::Cancel));
+            Box::new(|_| {})
+        },
+        "--msgbox" if items.is_empty() => {
+            tab.widget(finish_button("OK", &finished, Outcome::Ok));
+            Box::new(|_| {})
+        },
+        "--yesno" | "--msgbox" => usage_error(&format!("{dialog} takes only a text")),
+        _ => usage_error(&format!("unknown dialog {dialog}")),
+    };
+
+    let mut gui = builder.tab(&mut tab).build();
+    let outcome = run(&mut gui, &finished);
+    if outcome == Outcome::Ok {
+        report(&mut gui);
+    }
+    process::exit(outcome as i32);
+}
@@ -0,0 +1,218 @@
+use crate::{Binding, DateTime, WidgetState};
+use sdl2::{
+    controller::{Axis, Button},
+    keyboard::Keycode,
+};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Values of widgets with an ID, stored as `id=value` lines.
+#[derive(Debug)]
+pub(crate) struct Settings {
+    path: PathBuf,
+    values: BTreeMap<String, String>,
+    // changed since the last flush
+    dirty: bool,
+}
+
+impl Settings {
+    /// Missing or unreadable files start out empty, malformed lines are skipped.
+    pub(crate) fn load(path: PathBuf) -> Self {
+        let values = fs::read_to_string(&path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(id, value)| (id.trim().to_string(), value.trim().to_string()))
+            .collect();
+        Self { path, values, dirty: false }
+    }
+    pub(crate) fn get(&self, id: &str) -> Option<&str> {
+        self.values.get(id).map(String::as_str)
+    }
+    /// Stores `value`, the file is written by the next [`Settings::flush`] if it differs from what was stored.
+    pub(crate) fn set(&mut self, id: &str, value: String) {
+        if self.values.get(id) == Some(&value) {
+            return;
+        }
+        self.values.insert(id.to_string(), value);
+        self.dirty = true;
+    }
+    /// Writes the file if anything changed, a failed write is only retried after the next change.
+    pub(crate) fn flush(&mut self) -> Result<(), String> {
+        if !std::mem::take(&mut self.dirty) {
+            return Ok(());
+        }
+        self.save().map_err(|e| format!("Failed to save settings to {}: {e}", self.path.display()))
+    }
+    fn save(&self) -> io::Result<()> {
+        let text = self.values.iter()
+            .map(|(id, value)| format!("{id}={value}\n"))
+            .collect::<String>();
+        // written next to the real file and renamed over it, so a crash never leaves half a file
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        fs::write(&tmp, text)?;
+        fs::rename(&tmp, &self.path)
+    }
+}
+
+/// Text form of the value of a widget, None for widgets without one.
+pub(crate) fn encode(state: &WidgetState) -> Option<String> {
+    Some(match state {
+        WidgetState::Button => return None,
+        WidgetState::Toggle(on, _) | WidgetState::Radio(on, _) => on.to_string(),
+        WidgetState::Slider(value, _) => value.to_string(),
+        WidgetState::Color(r, g, b) => format!("{r},{g},{b}"),
+        WidgetState::DateTime(v) => format!("{:04}-{:02}-{:02} {:02}:{:02}", v.year, v.month, v.day, v.hour, v.minute),
+        WidgetState::Binding(None) => "none".to_string(),
+        WidgetState::Binding(Some(Binding::Key(key))) => format!("key:{}", key.name()),
+        WidgetState::Binding(Some(Binding::Button(button))) => format!("button:{}", button.string()),
+        WidgetState::Binding(Some(Binding::Axis(axis, positive))) => format!("axis:{}{}", axis.string(), if *positive { "+" } else { "-" }),
+    })
+}
+
+/// Replaces the value in `state` with the one in `text`, keeping it and returning false if `text`
+/// doesn't fit the kind of widget.
+pub(crate) fn decode(state: &mut WidgetState, text: &str) -> bool {
+    match state {
+        WidgetState::Toggle(on, opacity) | WidgetState::Radio(on, opacity) => {
+            let Ok(value) = text.parse() else { return false };
+            *on = value;
+            *opacity = if value { 255 } else { 0 };
+        },
+        WidgetState::Slider(value, display) => {
+            let Ok(v) = text.parse() else { return false };
+            (*value, *display) = (v, v);
+        },
+        WidgetState::Color(r, g, b) => {
+            let parts = text.split(',').map(|c| c.trim().parse::<u8>()).collect::<Vec<_>>();
+            let [Ok(nr), Ok(ng), Ok(nb)] = parts[..] else { return false };
+            (*r, *g, *b) = (nr, ng, nb);
+        },
+        WidgetState::DateTime(value) => {
+            let numbers = text.split(['-', ' ', ':']).map(|n| n.parse::<i32>()).collect::<Vec<_>>();
+            let [Ok(y), Ok(mo), Ok(d), Ok(h), Ok(mi)] = numbers[..] else { return false };
+            *value = DateTime::new(y, mo as u8, d as u8, h as u8, mi as u8);
+        },
+        WidgetState::Binding(binding) => {
+            let decoded = match text.split_once(':') {
+                Some(("key", name)) => Keycode::from_name(name).map(Binding::Key),
+                Some(("button", name)) => Button::from_string(name).map(Binding::Button),
+                Some(("axis", name)) if name.len() > 1 => {
+                    let (name, sign) = name.split_at(name.len() - 1);
+                    Axis::from_string(name).map(|axis| Binding::Axis(axis, sign == "+"))
+                },
+                _ if text == "none" => {
+                    *binding = None;
+                    return true;
+                },
+                _ => None,
+            };
+            if decoded.is_none() {
+                return false;
+            }
+            *binding = decoded;
+        },
+        WidgetState::Button => return false,
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("sgui2-test-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn round_trip() {
+        let states = [
+            WidgetState::Toggle(true, 255),
+            WidgetState::Radio(false, 0),
+            WidgetState::Slider(200, 200),
+            WidgetState::Color(1, 22, 255),
+            WidgetState::DateTime(DateTime::new(2024, 2, 29, 23, 5)),
+            WidgetState::Binding(None),
+        ];
+        for state in states {
+            let text = encode(&state).expect("state should have a text form");
+            let mut decoded = match state {
+                WidgetState::Toggle(..) => WidgetState::Toggle(false, 0),
+                WidgetState::Radio(..) => WidgetState::Radio(true, 255),
+                WidgetState::Slider(..) => WidgetState::Slider(0, 0),
+                WidgetState::Color(..) => WidgetState::Color(0, 0, 0),
+                WidgetState::DateTime(_) => WidgetState::DateTime(DateTime::new(2000, 1, 1, 0, 0)),
+                _ => WidgetState::Binding(Some(Binding::Button(Button::A))),
+            };
+            assert!(decode(&mut decoded, &text), "{text}");
+            assert_eq!(encode(&decoded), Some(text));
+        }
+        assert_eq!(encode(&WidgetState::DateTime(DateTime::new(2024, 2, 29, 23, 5))).as_deref(), Some("2024-02-29 23:05"));
+        assert_eq!(encode(&WidgetState::Button), None);
+    }
+
+    #[test]
+    fn bad_values_keep_the_state() {
+        let cases = [
+            (WidgetState::Toggle(true, 255), "maybe"),
+            (WidgetState::Slider(7, 7), "256"),
+            (WidgetState::Slider(7, 7), "-1"),
+            (WidgetState::Color(1, 2, 3), "1,2"),
+            (WidgetState::Color(1, 2, 3), "1,2,300"),
+            (WidgetState::DateTime(DateTime::new(2024, 1, 1, 0, 0)), "2024-01-01"),
+            (WidgetState::Binding(None), "nonsense"),
+            (WidgetState::Button, "true"),
+        ];
+        for (mut state, text) in cases {
+            let before = encode(&state);
+            assert!(!decode(&mut state, text), "{text}");
+            assert_eq!(encode(&state), before);
+        }
+    }
+
+    #[test]
+    fn load_skips_malformed_lines() {
+        let path = temp_path("malformed");
+        fs::write(&path, "volume = 12\nno equals sign\n\nname=a=b\n").expect("Failed to write test file");
+        let settings = Settings::load(path.clone());
+        let _ = fs::remove_file(&path);
+        assert_eq!(settings.get("volume"), Some("12"));
+        assert_eq!(settings.get("name"), Some("a=b"));
+        assert_eq!(settings.values.len(), 2);
+        assert_eq!(settings.get("unknown"), None);
+    }
+
+    #[test]
+    fn missing_file_starts_empty() {
+        let settings = Settings::load(temp_path("missing"));
+        assert!(settings.values.is_empty());
+    }
+
+    #[test]
+    fn flush_writes_changes_once() {
+        let path = temp_path("flush");
+        let mut settings = Settings::load(path.clone());
+        settings.set("b", "2".to_string());
+        settings.set("a", "1".to_string());
+        assert_eq!(settings.flush(), Ok(()));
+        let written = fs::read_to_string(&path);
+        let _ = fs::remove_file(&path);
+        assert_eq!(written.ok().as_deref(), Some("a=1\nb=2\n"));
+        // nothing changed, so nothing is written
+        settings.set("a", "1".to_string());
+        assert_eq!(settings.flush(), Ok(()));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn flush_reports_failures() {
+        let mut settings = Settings::load(temp_path("no-such-dir").join("settings"));
+        settings.set("a", "1".to_string());
+        assert!(settings.flush().is_err());
+        assert_eq!(settings.flush(), Ok(()));
+    }
+}
@@ -0,0 +1,271 @@
+use crate::{destroy_texture, theme::Theme, image::{self, ImageCache, ImageSource}};
+use sdl2::{
+    render::{self, Texture, TextureCreator},
+    video::{self, WindowContext},
+    rect::Rect,
+    ttf::Font,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Instant;
+
+enum Update {
+    Text(String, String),
+    Icon(String, ImageSource),
+    Remove(String),
+}
+
+/// Cloneable handle for changing the app-defined indicators of a [`StatusBar`], also from other threads.
+#[derive(Debug, Clone)]
+pub struct StatusHandle {
+    tx: Sender<Update>,
+}
+
+impl StatusHandle {
+    /// Shows `text` as the indicator `id`, which is added left of the others on first use.
+    pub fn set_text(&self, id: impl ToString, text: impl ToString) {
+        // receiver only goes away together with the Gui, nothing to report to then
+        let _ = self.tx.send(Update::Text(id.to_string(), text.to_string()));
+    }
+    /// Shows an image as the indicator `id`, scaled to the font height.
+    pub fn set_icon(&self, id: impl ToString, source: impl Into<ImageSource>) {
+        let _ = self.tx.send(Update::Icon(id.to_string(), source.into()));
+    }
+    pub fn remove(&self, id: impl ToString) {
+        let _ = self.tx.send(Update::Remove(id.to_string()));
+    }
+}
+
+enum Content {
+    // text is kept to skip re-rendering when nothing changed
+    Text(String, Texture),
+    Icon(Rc<Texture>),
+}
+
+fn destroy_content(content: Content) {
+    match content {
+        Content::Text(_, texture) => destroy_texture(texture),
+        // cached files are shared with the rest of the Gui
+        Content::Icon(icon) => if let Ok(texture) = Rc::try_unwrap(icon) {
+            destroy_texture(texture);
+        },
+    }
+}
+
+/// Strip along the top of the window with a clock, a battery gauge and indicators set through [`StatusHandle`].
+pub struct StatusBar {
+    clock: bool,
+    battery: Option<PathBuf>,
+    tx: Sender<Update>,
+    rx: Receiver<Update>,
+    indicators: Vec<(String, Content)>,
+    clock_text: Option<Content>,
+    battery_text: Option<Content>,
+    next_battery_read: Instant,
+}
+
+impl Default for StatusBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatusBar {
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self {
+            clock: false,
+            battery: None,
+            tx,
+            rx,
+            indicators: Vec::new(),
+            clock_text: None,
+            battery_text: None,
+            next_battery_read: Instant::now(),
+        }
+    }
+    /// Shows the local time in the right corner.
+    pub fn clock(mut self) -> Self {
+        self.clock = true;
+        self
+    }
+    /// Shows the charge of the power supply at `path`, e.g. `/sys/class/power_supply/BAT0`,
+    /// read from its `capacity` and `status` files.
+    pub fn battery(mut self, path: impl Into<PathBuf>) -> Self {
+        self.battery = Some(path.into());
+        self
+    }
+    /// Returns a handle for adding and changing indicators, meant to be moved into widget callbacks.
+    pub fn handle(&self) -> StatusHandle {
+        StatusHandle { tx: self.tx.clone() }
+    }
+    pub(crate) fn height(font_height: i32) -> u32 {
+        let pad = Theme::padding() as i32 * font_height / 100;
+        (font_height + pad) as u32
+    }
+    fn clock_text() -> String {
+        // SAFETY: localtime_r only writes to the struct passed in
+        let tm = unsafe {
+            let now = libc::time(std::ptr::null_mut());
+            let mut tm: libc::tm = std::mem::zeroed();
+            libc::localtime_r(&now, &mut tm);
+            tm
+        };
+        format!("{:02}:{:02}", tm.tm_hour, tm.tm_min)
+    }
+    fn battery_text(path: &Path) -> Option<String> {
+        let capacity = fs::read_to_string(path.join("capacity")).ok()?.trim().parse::<u8>().ok()?;
+        let status = fs::read_to_string(path.join("status")).unwrap_or_default();
+        let charging = if status.trim() == "Charging" { "+" } else { "" };
+        Some(format!("{charging}{capacity}%"))
+    }
+    fn render(old: &mut Option<Content>, text: Option<String>, font: &Font, texture_creator: &TextureCreator<WindowContext>) {
+        let Some(text) = text else {
+            if let Some(old) = old.take() {
+                destroy_content(old);
+            }
+            return;
+        };
+        if let Some(Content::Text(current, _)) = old {
+            if *current == text {
+                return;
+            }
+        }
+        let new = Content::Text(text.clone(), render_text(&text, font, texture_creator));
+        if let Some(old) = old.replace(new) {
+            destroy_content(old);
+        }
+    }
+    fn update(&mut self, font: &Font, texture_creator: &TextureCreator<WindowContext>, images: &mut ImageCache) {
+        for update in self.rx.try_iter().collect::<Vec<_>>() {
+            let (id, content) = match update {
+                Update::Remove(id) => {
+                    if let Some(i) = self.indicators.iter().position(|(i, _)| *i == id) {
+                        destroy_content(self.indicators.remove(i).1);
+                    }
+                    continue;
+                },
+                Update::Text(id, text) => {
+                    let texture = render_text(&text, font, texture_creator);
+                    (id, Content::Text(text, texture))
+                },
+                Update::Icon(id, source) => (id, Content::Icon(images.load(source, texture_creator))),
+            };
+            match self.indicators.iter_mut().find(|(i, _)| *i == id) {
+                Some((_, old)) => destroy_content(std::mem::replace(old, content)),
+                None => self.indicators.push((id, content)),
+            }
+        }
+
+        if self.clock {
+            Self::render(&mut self.clock_text, Some(Self::clock_text()), font, texture_creator);
+        }
+        if let Some(path) = &self.battery {
+            if Instant::now() > self.next_battery_read {
+                self.next_battery_read = Instant::now() + Theme::battery_interval();
+                let text = Self::battery_text(path);
+                Self::render(&mut self.battery_text, text, font, texture_creator);
+            }
+        }
+    }
+    pub(crate) fn draw(&mut self, canvas: &mut render::Canvas<video::Window>, font: &Font, texture_creator: &TextureCreator<WindowContext>, images: &mut ImageCache, width: u32) {
+        self.update(font, texture_creator, images);
+
+        let height = Self::height(font.height());
+        canvas.set_viewport(None);
+        canvas.set_draw_color(Theme::bg_tabs());
+        canvas.fill_rect(Rect::new(0, 0, width, height)).expect("Failed to draw status bar");
+
+        let pad = Theme::padding() as i32 * font.height() / 100;
+        let text_height = font.height() as u32;
+        let mut x = width as i32 - pad;
+        // right to left, the clock sits in the corner
+        let items = self.clock_text.iter()
+            .chain(self.battery_text.iter())
+            .chain(self.indicators.iter().rev().map(|(_, c)| c));
+        for content in items {
+            let (texture, w, h): (&Texture, u32, u32) = match content {
+                Content::Text(_, texture) => {
+                    let q = texture.query();
+                    (texture, q.width, q.height)
+                },
+                Content::Icon(icon) => {
+                    let (w, h) = image::scaled_to_height(icon, text_height);
+                    (icon, w, h)
+                },
+            };
+            x -= w as i32;
+            let y = (height as i32 - h as i32) / 2;
+            canvas.copy(texture, None, Rect::new(x, y, w, h)).expect("Failed to draw status bar");
+            x -= pad;
+        }
+    }
+}
+
+fn render_text(text: &str, font: &Font, texture_creator: &TextureCreator<WindowContext>) -> Texture {
+    let text = if text.is_empty() { " " } else { text };
+    let surface = font.render(text).blended(Theme::fg_widgets()).expect("Failed to render text");
+    texture_creator.create_texture_from_surface(&surface).expect("Failed to create texture from surface")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Power supply directory holding the given files, removed again on drop.
+    struct Supply(PathBuf);
+
+    impl Supply {
+        fn new(name: &str, files: &[(&str, &str)]) -> Self {
+            let dir = std::env::temp_dir().join(format!("sgui2-test-{}-{name}", std::process::id()));
+            fs::create_dir_all(&dir).expect("Failed to create test directory");
+            for (file, content) in files {
+                fs::write(dir.join(file), content).expect("Failed to write test file");
+            }
+            Self(dir)
+        }
+    }
+
+    impl Drop for Supply {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn charging() {
+        let supply = Supply::new("charging", &[("capacity", "42\n"), ("status", "Charging\n")]);
+        assert_eq!(StatusBar::battery_text(&supply.0).as_deref(), Some("+42%"));
+    }
+
+    #[test]
+    fn discharging() {
+        let supply = Supply::new("discharging", &[("capacity", "97\n"), ("status", "Discharging\n")]);
+        assert_eq!(StatusBar::battery_text(&supply.0).as_deref(), Some("97%"));
+        let supply = Supply::new("no-status", &[("capacity", "5\n")]);
+        assert_eq!(StatusBar::battery_text(&supply.0).as_deref(), Some("5%"));
+    }
+
+    #[test]
+    fn missing_capacity() {
+        let supply = Supply::new("missing", &[("status", "Full\n")]);
+        assert_eq!(StatusBar::battery_text(&supply.0), None);
+    }
+
+    #[test]
+    fn garbage_capacity() {
+        let supply = Supply::new("garbage", &[("capacity", "lots\n"), ("status", "Charging\n")]);
+        assert_eq!(StatusBar::battery_text(&supply.0), None);
+    }
+
+    #[test]
+    fn clock_is_hours_and_minutes() {
+        let text = StatusBar::clock_text();
+        let (hours, minutes) = text.split_once(':').expect("clock should have a colon");
+        assert_eq!((hours.len(), minutes.len()), (2, 2));
+        assert!(hours.parse::<u8>().is_ok_and(|h| h < 24), "{text}");
+        assert!(minutes.parse::<u8>().is_ok_and(|m| m < 60), "{text}");
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{destroy_texture, control::{Context, Control}};
+use sdl2::{
+    render::{Texture, TextureCreator},
+    video::WindowContext,
+    rect::Rect,
+    ttf::Font,
+};
+use std::mem;
+
+fn fits(font: &Font, text: &str, width: u32) -> bool {
+    font.size_of(text).map(|(w, _)| w <= width).unwrap_or(false)
+}
+
+/// Splits `text` into lines no wider than `width`, breaking on spaces where possible
+/// and keeping explicit newlines.
+pub(crate) fn wrap(text: &str, font: &Font, width: u32) -> Vec<String> {
+    wrap_by(text, |line| fits(font, line, width))
+}
+
+/// [`wrap`] with `fits` telling whether a line is narrow enough.
+fn wrap_by(text: &str, fits: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split(' ') {
+            let candidate = if line.is_empty() { word.to_string() } else { format!("{line} {word}") };
+            if fits(&candidate) {
+                line = candidate;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(mem::take(&mut line));
+            }
+            // a single word wider than the whole line gets broken anywhere
+            for c in word.chars() {
+                line.push(c);
+                if !fits(&line) && line.chars().count() > 1 {
+                    line.pop();
+                    lines.push(mem::take(&mut line));
+                    line.push(c);
+                }
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Word-wrapped text, re-rendered whenever the available width changes.
+pub(crate) struct Paragraph {
+    text: String,
+    width: u32,
+    lines: Vec<Texture>,
+    line_height: u32,
+}
+
+impl Paragraph {
+    pub(crate) fn new(text: String) -> Self {
+        Self {
+            text,
+            width: 0,
+            lines: Vec::new(),
+            line_height: 0,
+        }
+    }
+    fn layout(&mut self, width: u32, font: &Font, texture_creator: &TextureCreator<WindowContext>) {
+        if width == self.width || width == 0 {
+            return;
+        }
+        self.width = width;
+        self.line_height = font.recommended_line_spacing().max(font.height()) as u32;
+        for old in self.lines.drain(..) {
+            destroy_texture(old);
+        }
+        self.lines = wrap(&self.text, font, width).iter()
+            .map(|line| {
+                let line = if line.is_empty() { " " } else { line };
+                let surface = font.render(line).blended((255, 255, 255)).expect("Failed to render text");
+                texture_creator.create_texture_from_surface(&surface).expect("Failed to create texture from surface")
+            })
+            .collect();
+    }
+}
+
+impl Control for Paragraph {
+    fn measure(&mut self, ctx: &mut Context) -> u32 {
+        self.layout(ctx.width, ctx.font, ctx.texture_creator);
+        self.lines.len() as u32 * self.line_height
+    }
+    fn draw(&mut self, ctx: &mut Context) {
+        for (i, line) in self.lines.iter().enumerate() {
+            let q = line.query();
+            let y = i as i32 * self.line_height as i32;
+            ctx.canvas.copy(line, None, Rect::new(0, y, q.width, q.height)).expect("Failed to draw a paragraph");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wrap_at(text: &str, width: usize) -> Vec<String> {
+        wrap_by(text, |line| line.chars().count() <= width)
+    }
+
+    #[test]
+    fn breaks_on_spaces() {
+        assert_eq!(wrap_at("the quick brown fox", 10), ["the quick", "brown fox"]);
+    }
+
+    #[test]
+    fn keeps_newlines_and_empty_lines() {
+        assert_eq!(wrap_at("one\n\ntwo", 10), ["one", "", "two"]);
+        assert_eq!(wrap_at("", 10), [""]);
+    }
+
+    #[test]
+    fn breaks_long_words_anywhere() {
+        assert_eq!(wrap_at("ab abcdefgh", 3), ["ab", "abc", "def", "gh"]);
+    }
+
+    #[test]
+    fn keeps_a_character_wider_than_the_line() {
+        assert_eq!(wrap_at("ab", 0), ["a", "b"]);
+    }
+}
@@ -1,7 +1,9 @@
 #![allow(dead_code)]
+use crate::toast::Severity;
 use std::time::Duration;
 
 // TODO: load in runtime
+/// Colors and sizes used by sgui2, custom [`Control`](crate::Control)s should use them too.
 #[derive(Debug)]
 pub struct Theme {
     font: &'static str,
@@ -31,4 +33,96 @@ impl Theme {
     pub fn idle_timeout() -> Duration {
         Duration::from_secs(2)
     }
+    pub fn toast_bg(severity: Severity) -> (u8, u8, u8) {
+        match severity {
+            Severity::Info => (40, 60, 90),
+            Severity::Warning => (110, 80, 20),
+            Severity::Error => (120, 30, 30),
+        }
+    }
+    pub fn toast_fg(severity: Severity) -> (u8, u8, u8) {
+        match severity {
+            Severity::Info => (220, 230, 250),
+            Severity::Warning => (255, 235, 200),
+            Severity::Error => (255, 220, 220),
+        }
+    }
+    pub fn toast_max_visible() -> usize {
+        3
+    }
+    pub fn log_stderr() -> (u8, u8, u8) {
+        (255, 120, 120)
+    }
+    pub fn log_rows() -> usize {
+        8
+    }
+    /// Side of a grid tile, in font heights.
+    pub fn tile_scale() -> u32 {
+        5
+    }
+    /// Height of the chart area of a graph, in lines of text.
+    pub fn graph_rows() -> u32 {
+        4
+    }
+    pub fn graph_series(index: usize) -> (u8, u8, u8) {
+        const PALETTE: [(u8, u8, u8); 4] = [
+            (250, 250, 250),
+            (90, 170, 250),
+            (250, 170, 60),
+            (120, 220, 120),
+        ];
+        PALETTE[index % PALETTE.len()]
+    }
+    pub fn color_presets() -> &'static [(u8, u8, u8)] {
+        &[
+            (255, 255, 255),
+            (255, 0, 0),
+            (255, 128, 0),
+            (255, 255, 0),
+            (0, 255, 0),
+            (0, 255, 255),
+            (0, 0, 255),
+            (255, 0, 255),
+        ]
+    }
+    /// How long key capture widgets wait for input before giving up.
+    pub fn capture_timeout() -> Duration {
+        Duration::from_secs(5)
+    }
+    /// How long Back has to be held to cancel a key capture instead of binding Back itself.
+    pub fn capture_cancel_hold() -> Duration {
+        Duration::from_millis(800)
+    }
+    /// Smallest part of the window the tab bar takes in the two panel layout.
+    pub fn tab_bar_min() -> f32 {
+        0.15
+    }
+    /// Largest part of the window the tab bar takes, longer tab names scroll.
+    pub fn tab_bar_max() -> f32 {
+        0.4
+    }
+    /// Opacity of the background drawn over disabled widgets.
+    pub fn disabled_fade() -> u8 {
+        170
+    }
+    /// Descriptions longer than this get cut off.
+    pub fn footer_max_rows() -> usize {
+        3
+    }
+    /// How often the battery indicator of the status bar is refreshed.
+    pub fn battery_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+    /// How long failures of commands behind widgets stay on screen.
+    pub fn command_error_duration() -> Duration {
+        Duration::from_secs(6)
+    }
+    /// How long commands behind widgets may run before they are killed.
+    pub fn command_timeout() -> Duration {
+        Duration::from_secs(10)
+    }
+    /// Time the busy bar of a widget waiting for a command takes to sweep across the row.
+    pub fn busy_sweep() -> Duration {
+        Duration::from_millis(1200)
+    }
 }
@@ -0,0 +1,155 @@
+use crate::{closerize, destroy_texture, theme::Theme};
+use derivative::Derivative;
+use serde::Deserialize;
+use sdl2::{
+    render::{self, Texture, TextureCreator},
+    video::{self, WindowContext},
+    rect::Rect,
+    ttf::Font,
+};
+use std::collections::VecDeque;
+use std::mem;
+use std::sync::mpsc::{self, Sender, Receiver};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug)]
+pub(crate) struct Notification {
+    text: String,
+    duration: Duration,
+    severity: Severity,
+}
+
+/// Cloneable handle for showing toasts, can be moved into widget callbacks
+/// or other threads.
+#[derive(Debug, Clone)]
+pub struct Notifier {
+    pub(crate) tx: Sender<Notification>,
+}
+
+impl Notifier {
+    pub fn notify(&self, text: impl ToString, duration: Duration, severity: Severity) {
+        // receiver only goes away together with the Gui, nothing to report to then
+        let _ = self.tx.send(Notification {
+            text: text.to_string(),
+            duration,
+            severity,
+        });
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+struct Toast {
+    #[derivative(Debug="ignore")]
+    text: Texture,
+    severity: Severity,
+    expires: Instant,
+    // current position, animated towards the target each frame
+    x_offset: i32,
+    y: i32,
+    leaving: bool,
+}
+
+#[derive(Debug)]
+pub(crate) struct Toasts {
+    tx: Sender<Notification>,
+    rx: Receiver<Notification>,
+    queue: VecDeque<Notification>,
+    shown: Vec<Toast>,
+}
+
+impl Toasts {
+    pub(crate) fn channel() -> (Sender<Notification>, Receiver<Notification>) {
+        mpsc::channel()
+    }
+    pub(crate) fn new(tx: Sender<Notification>, rx: Receiver<Notification>) -> Self {
+        Self {
+            tx,
+            rx,
+            queue: VecDeque::new(),
+            shown: Vec::new(),
+        }
+    }
+    pub(crate) fn notifier(&self) -> Notifier {
+        Notifier { tx: self.tx.clone() }
+    }
+    pub(crate) fn tick(&mut self, font: &Font, texture_creator: &TextureCreator<WindowContext>, window_size: (u32, u32)) {
+        self.queue.extend(self.rx.try_iter());
+
+        let (width, height) = window_size;
+        let pad = (Theme::padding() as i32 * font.height()) / 100;
+        let now = Instant::now();
+
+        while self.shown.iter().filter(|t| !t.leaving).count() < Theme::toast_max_visible() {
+            let Some(note) = self.queue.pop_front() else { break };
+            // SDL_ttf refuses empty text, and text it can't render isn't worth taking the Gui down for
+            let Some(text) = Self::render(&note, font, texture_creator) else { continue };
+            self.shown.push(Toast {
+                text,
+                severity: note.severity,
+                expires: now + note.duration,
+                x_offset: width as i32,
+                y: height as i32 - pad,
+                leaving: false,
+            });
+        }
+
+        let mut bottom = height as i32 - pad;
+        for toast in self.shown.iter_mut() {
+            let q = toast.text.query();
+            if now > toast.expires {
+                toast.leaving = true;
+            }
+            let target = if toast.leaving { q.width as i32 + pad * 3 } else { 0 };
+            toast.x_offset = closerize(toast.x_offset, target);
+            bottom -= q.height as i32 + pad * 2;
+            toast.y = closerize(toast.y, bottom);
+            bottom -= pad;
+        }
+        let (gone, shown) = mem::take(&mut self.shown).into_iter()
+            .partition(|t| t.leaving && t.x_offset >= t.text.query().width as i32 + pad * 3);
+        self.shown = shown;
+        for toast in gone {
+            destroy_texture(toast.text);
+        }
+    }
+    fn render(note: &Notification, font: &Font, texture_creator: &TextureCreator<WindowContext>) -> Option<Texture> {
+        if note.text.is_empty() {
+            return None;
+        }
+        let surface = font.render(&note.text).blended(Theme::toast_fg(note.severity)).ok()?;
+        texture_creator.create_texture_from_surface(&surface).ok()
+    }
+    pub(crate) fn draw(&self, canvas: &mut render::Canvas<video::Window>, font_height: i32) {
+        let width = canvas.viewport().width() as i32;
+        let pad = (Theme::padding() as i32 * font_height) / 100;
+        for toast in self.shown.iter() {
+            let q = toast.text.query();
+            let w = q.width + pad as u32 * 2;
+            let h = q.height + pad as u32 * 2;
+            let x = width - w as i32 - pad + toast.x_offset;
+            let bg = Rect::new(x, toast.y, w, h);
+            canvas.set_draw_color(Theme::toast_bg(toast.severity));
+            canvas.fill_rect(bg).expect("Failed to draw toast");
+            canvas.set_draw_color(Theme::toast_fg(toast.severity));
+            canvas.draw_rect(bg).expect("Failed to draw toast");
+            canvas.copy(&toast.text, None, Rect::new(x + pad, toast.y + pad, q.width, q.height))
+                .expect("Failed to draw toast");
+        }
+    }
+    pub(crate) fn push(&mut self, text: impl ToString, duration: Duration, severity: Severity) {
+        self.queue.push_back(Notification {
+            text: text.to_string(),
+            duration,
+            severity,
+        });
+    }
+}